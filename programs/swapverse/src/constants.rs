@@ -1,5 +1,6 @@
 pub const GLOBAL_STATE_SEED: &str = "global-state";
 pub const SIGNING_AUTHORITY_SEED: &str = "signing-authority";
+pub const TOKEN_MINT_REGISTRY_SEED: &str = "token-mint-registry";
 
 pub const USDC_DEV_TOKEN_SEED: &str = "usdc-dev";
 pub const USDT_DEV_TOKEN_SEED: &str = "usdt-dev";
@@ -10,3 +11,36 @@ pub const USDH_DEV_TOKEN_SEED: &str = "usdh-dev";
 pub const SWAP_POOL_SEED: &str = "swap-pool";
 pub const SWAP_POOL_SHARE_TOKEN_SEED: &str = "pool-share-token";
 pub const SWAP_POOL_TREASURY_ACCOUNT_SEED: &str = "treasury-account";
+pub const MULTI_ASSET_POOL_SEED: &str = "multi-asset-pool";
+
+pub const SECONDS_IN_A_DAY: i64 = 24 * 60 * 60;
+
+// fees are in basis points of this
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub const DEFAULT_MIN_SWAP_FEE_BPS: u16 = 0;
+pub const DEFAULT_MAX_SWAP_FEE_BPS: u16 = 1_000;
+pub const DEFAULT_MIN_SWAPVERSE_FEE_BPS: u16 = 500;
+pub const DEFAULT_MAX_SWAPVERSE_FEE_BPS: u16 = 5_000;
+pub const DEFAULT_MAX_REFERRAL_SHARE_BPS: u16 = 2_000;
+
+pub const MIN_AMP_COEFFICIENT: u64 = 1;
+pub const MAX_AMP_COEFFICIENT: u64 = 10_000;
+
+// prices returned by the swap curves are scaled by this
+pub const PRICE_SCALE: u128 = 1_000_000_000_000;
+// fixed ratio of a pool is the token b amount for FIXED_RATIO_PRECISION units of token a, both
+// normalized to the decimals of the mint with more decimals
+pub const FIXED_RATIO_PRECISION: u64 = 1_000_000;
+
+// swap pool, pool token in account, pool token out account, pool treasury token out account
+// and user token out account
+pub const ACCOUNTS_PER_ROUTE_HOP: usize = 5;
+pub const MAX_ROUTE_HOPS: usize = 4;
+
+// fee on flash loans, goes to the pool profit
+pub const FLASH_LOAN_FEE_BPS: u64 = 9;
+
+// number of tokens a multi asset pool can hold
+pub const MIN_MULTI_ASSET_POOL_TOKENS: usize = 3;
+pub const MAX_MULTI_ASSET_POOL_TOKENS: usize = 8;
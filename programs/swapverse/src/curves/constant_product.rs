@@ -0,0 +1,47 @@
+// x * y = k priced against the live pool reserves
+
+use super::{checked, SwapCurve};
+use crate::constants::PRICE_SCALE;
+use crate::error::SwapverseError;
+use anchor_lang::prelude::*;
+
+pub struct ConstantProduct;
+
+impl SwapCurve for ConstantProduct {
+    // rounded down so that k never decreases
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        let new_reserve_in_u128 = checked((reserve_in as u128).checked_add(amount_in as u128))?;
+
+        let amount_out = checked(
+            (reserve_out as u128)
+                .checked_mul(amount_in as u128)
+                .and_then(|value| value.checked_div(new_reserve_in_u128)),
+        )?;
+        Ok(amount_out as u64)
+    }
+
+    // rounded up so that k never decreases
+    fn swap_in(&self, amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(
+            amount_out < reserve_out,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        let new_reserve_out_u128 = (reserve_out - amount_out) as u128;
+        let amount_in = checked(
+            (reserve_in as u128)
+                .checked_mul(amount_out as u128)
+                .and_then(|value| value.checked_add(new_reserve_out_u128 - 1))
+                .and_then(|value| value.checked_div(new_reserve_out_u128)),
+        )?;
+        u64::try_from(amount_in).map_err(|_| error!(SwapverseError::MathOverflow))
+    }
+
+    fn spot_price(&self, reserve_in: u64, reserve_out: u64) -> Result<u128> {
+        checked(
+            (reserve_out as u128)
+                .checked_mul(PRICE_SCALE)
+                .and_then(|value| value.checked_div(reserve_in as u128)),
+        )
+    }
+}
@@ -0,0 +1,32 @@
+// x + y = k, tokens are swapped 1:1 as long as the pool has reserves of token out
+
+use super::SwapCurve;
+use crate::constants::PRICE_SCALE;
+use crate::error::SwapverseError;
+use anchor_lang::prelude::*;
+
+pub struct ConstantSum;
+
+impl SwapCurve for ConstantSum {
+    fn swap_out(&self, amount_in: u64, _reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(
+            amount_in <= reserve_out,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        Ok(amount_in)
+    }
+
+    fn swap_in(&self, amount_out: u64, _reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(
+            amount_out <= reserve_out,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        Ok(amount_out)
+    }
+
+    fn spot_price(&self, _reserve_in: u64, _reserve_out: u64) -> Result<u128> {
+        Ok(PRICE_SCALE)
+    }
+}
@@ -0,0 +1,54 @@
+// tokens are swapped at a fixed rate of numerator / denominator, bounded by the reserves
+
+use super::{checked, SwapCurve};
+use crate::constants::PRICE_SCALE;
+use crate::error::SwapverseError;
+use anchor_lang::prelude::*;
+
+pub struct FixedRatio {
+    // units of token out given for denominator units of token in
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl SwapCurve for FixedRatio {
+    // rounded down
+    fn swap_out(&self, amount_in: u64, _reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        let amount_out = checked(
+            (amount_in as u128)
+                .checked_mul(self.numerator as u128)
+                .and_then(|value| value.checked_div(self.denominator as u128)),
+        )?;
+        require!(
+            amount_out <= reserve_out as u128,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        Ok(amount_out as u64)
+    }
+
+    // rounded up
+    fn swap_in(&self, amount_out: u64, _reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(
+            amount_out <= reserve_out,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        let numerator = self.numerator as u128;
+        let amount_in = checked(
+            (amount_out as u128)
+                .checked_mul(self.denominator as u128)
+                .and_then(|value| value.checked_add(numerator.checked_sub(1)?))
+                .and_then(|value| value.checked_div(numerator)),
+        )?;
+        u64::try_from(amount_in).map_err(|_| error!(SwapverseError::MathOverflow))
+    }
+
+    fn spot_price(&self, _reserve_in: u64, _reserve_out: u64) -> Result<u128> {
+        checked(
+            PRICE_SCALE
+                .checked_mul(self.numerator as u128)
+                .and_then(|value| value.checked_div(self.denominator as u128)),
+        )
+    }
+}
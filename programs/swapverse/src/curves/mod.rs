@@ -0,0 +1,80 @@
+pub mod constant_product;
+pub mod constant_sum;
+pub mod fixed_ratio;
+pub mod stable_swap;
+
+use crate::error::SwapverseError;
+use anchor_lang::prelude::*;
+
+pub use constant_product::ConstantProduct;
+pub use constant_sum::ConstantSum;
+pub use fixed_ratio::FixedRatio;
+pub use stable_swap::StableSwap;
+
+// pricing of a pool, always seen from the side of the token going in
+pub trait SwapCurve {
+    // amount of token out for an exact amount of token in, rounded in favour of the pool
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64>;
+
+    // amount of token in needed for an exact amount of token out, rounded in favour of the pool
+    fn swap_in(&self, amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64>;
+
+    // price of one token in, in token out, scaled by PRICE_SCALE
+    fn spot_price(&self, reserve_in: u64, reserve_out: u64) -> Result<u128>;
+}
+
+pub(crate) fn checked<T>(value: Option<T>) -> Result<T> {
+    value.ok_or_else(|| error!(SwapverseError::MathOverflow))
+}
+
+// a * b + addend, divided by divisor and rounded down. the intermediate value is kept in 256
+// bits, so only a result which does not fit in u128 is an overflow
+pub(crate) fn mul_add_div(a: u128, b: u128, addend: u128, divisor: u128) -> Result<u128> {
+    require!(divisor > 0, SwapverseError::MathOverflow);
+    if let Some(value) = a.checked_mul(b).and_then(|value| value.checked_add(addend)) {
+        return Ok(value / divisor);
+    }
+
+    let (high, low) = widening_mul(a, b);
+    let (low, carry) = low.overflowing_add(addend);
+    // a * b is at most (2^128 - 1)^2, the carry can not overflow the high half
+    let high = high + carry as u128;
+    require!(high < divisor, SwapverseError::MathOverflow);
+
+    // long division one bit at a time, the remainder stays below the divisor
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let overflow = remainder >> 127 == 1;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if overflow || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+
+    Ok(quotient)
+}
+
+pub(crate) fn mul_div(a: u128, b: u128, divisor: u128) -> Result<u128> {
+    mul_add_div(a, b, 0, divisor)
+}
+
+// high and low halves of the 256 bit product
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & mask);
+    let (b_high, b_low) = (b >> 64, b & mask);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (high_low & mask) + (low_high & mask);
+    let low = (low_low & mask) | (middle << 64);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+
+    (high, low)
+}
@@ -0,0 +1,177 @@
+// curve style stable swap invariant
+// A * n^n * sum(x) + D = A * D * n^n + D^(n + 1) / (n^n * prod(x))
+// all the math is done on u128 with checked operations, products of the invariant are divided
+// in 256 bits. reserves whose invariant does not fit return an overflow error instead of a
+// wrong price
+
+use super::{checked, mul_add_div, mul_div, SwapCurve};
+use crate::constants::PRICE_SCALE;
+use crate::error::SwapverseError;
+use anchor_lang::prelude::*;
+
+const MAX_ITERATIONS: u8 = 32;
+
+pub struct StableSwap {
+    pub amp_coefficient: u64,
+    // invariant of the reserves the curve is used with, computed on every call when not given
+    pub invariant: Option<u128>,
+}
+
+impl StableSwap {
+    fn invariant_of(&self, reserves: &[u128]) -> Result<u128> {
+        match self.invariant {
+            Some(d) => Ok(d),
+            None => compute_d(self.amp_coefficient, reserves),
+        }
+    }
+}
+
+// A * n^n
+fn amp_times_n_pow_n(amp_coefficient: u64, n_coins: u128) -> Result<u128> {
+    checked((amp_coefficient as u128).checked_mul(checked(n_coins.checked_pow(n_coins as u32))?))
+}
+
+// invariant D of the reserves, found with newton iterations
+pub fn compute_d(amp_coefficient: u64, reserves: &[u128]) -> Result<u128> {
+    let n_coins = reserves.len() as u128;
+    let sum = reserves
+        .iter()
+        .try_fold(0u128, |sum, reserve| sum.checked_add(*reserve));
+    let sum = checked(sum)?;
+    if sum == 0 {
+        return Ok(0);
+    }
+
+    let ann = amp_times_n_pow_n(amp_coefficient, n_coins)?;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // D^(n + 1) / (n^n * prod(x)), built one reserve at a time to stay in range
+        let mut d_product = d;
+        for reserve in reserves {
+            d_product = mul_div(d_product, d, checked(reserve.checked_mul(n_coins))?)?;
+        }
+
+        // D = (Ann * sum + D_P * n) * D / ((Ann - 1) * D + (n + 1) * D_P)
+        let d_previous = d;
+        let numerator = checked(
+            ann.checked_mul(sum)
+                .and_then(|value| value.checked_add(d_product.checked_mul(n_coins)?)),
+        )?;
+        let denominator = checked(
+            ann.checked_sub(1)
+                .and_then(|value| value.checked_mul(d))
+                .and_then(|value| {
+                    value.checked_add(n_coins.checked_add(1)?.checked_mul(d_product)?)
+                }),
+        )?;
+        d = mul_div(numerator, d, denominator)?;
+
+        if d.abs_diff(d_previous) <= 1 {
+            return Ok(d);
+        }
+    }
+
+    err!(SwapverseError::StableSwapDidNotConverge)
+}
+
+// reserve of token out which keeps the invariant d once token in has the given reserve
+pub fn compute_y(
+    amp_coefficient: u64,
+    reserves: &[u128],
+    token_in_index: usize,
+    token_out_index: usize,
+    new_reserve_in: u128,
+    d: u128,
+) -> Result<u128> {
+    let n_coins = reserves.len() as u128;
+    let ann = amp_times_n_pow_n(amp_coefficient, n_coins)?;
+
+    let mut c = d;
+    let mut sum = 0u128;
+    for (index, reserve) in reserves.iter().enumerate() {
+        if index == token_out_index {
+            continue;
+        }
+        let reserve = if index == token_in_index {
+            new_reserve_in
+        } else {
+            *reserve
+        };
+        sum = checked(sum.checked_add(reserve))?;
+        c = mul_div(c, d, checked(reserve.checked_mul(n_coins))?)?;
+    }
+    c = mul_div(c, d, checked(ann.checked_mul(n_coins))?)?;
+    let b = checked(d.checked_div(ann).and_then(|value| value.checked_add(sum)))?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_previous = y;
+        // y = (y^2 + c) / (2y + b - D)
+        let denominator = checked(
+            y.checked_mul(2)
+                .and_then(|value| value.checked_add(b))
+                .and_then(|value| value.checked_sub(d)),
+        )?;
+        y = mul_add_div(y, y, c, denominator)?;
+
+        if y.abs_diff(y_previous) <= 1 {
+            return Ok(y);
+        }
+    }
+
+    err!(SwapverseError::StableSwapDidNotConverge)
+}
+
+impl SwapCurve for StableSwap {
+    // one unit of token out is kept by the pool to cover the rounding of the newton iterations
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        let reserves = [reserve_in as u128, reserve_out as u128];
+        let d = self.invariant_of(&reserves)?;
+        let new_reserve_in = checked(reserves[0].checked_add(amount_in as u128))?;
+        let new_reserve_out = compute_y(self.amp_coefficient, &reserves, 0, 1, new_reserve_in, d)?;
+
+        Ok(reserves[1]
+            .saturating_sub(new_reserve_out)
+            .saturating_sub(1) as u64)
+    }
+
+    // one unit of token in is added for the same reason
+    fn swap_in(&self, amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(
+            amount_out < reserve_out,
+            SwapverseError::InsufficientPoolReserves
+        );
+
+        let reserves = [reserve_in as u128, reserve_out as u128];
+        let d = self.invariant_of(&reserves)?;
+        let new_reserve_out = checked(reserves[1].checked_sub(amount_out as u128))?;
+        let new_reserve_in = compute_y(self.amp_coefficient, &reserves, 1, 0, new_reserve_out, d)?;
+
+        let amount_in = checked(new_reserve_in.saturating_sub(reserves[0]).checked_add(1))?;
+        u64::try_from(amount_in).map_err(|_| error!(SwapverseError::MathOverflow))
+    }
+
+    // price = (Ann + D^3 / (4 * x^2 * y)) / (Ann + D^3 / (4 * x * y^2)) for x = reserve in
+    fn spot_price(&self, reserve_in: u64, reserve_out: u64) -> Result<u128> {
+        let reserves = [reserve_in as u128, reserve_out as u128];
+        let d = self.invariant_of(&reserves)?;
+        let ann_scaled =
+            checked(amp_times_n_pow_n(self.amp_coefficient, 2)?.checked_mul(PRICE_SCALE))?;
+
+        // D^3 / (4 * first^2 * second), scaled
+        let term = |first: u128, second: u128| -> Result<u128> {
+            let first_doubled = checked(first.checked_mul(2))?;
+            let value = mul_div(d, PRICE_SCALE, first_doubled)?;
+            let value = mul_div(value, d, first_doubled)?;
+            mul_div(value, d, second)
+        };
+        let numerator = checked(ann_scaled.checked_add(term(reserves[0], reserves[1])?))?;
+        let denominator = checked(ann_scaled.checked_add(term(reserves[1], reserves[0])?))?;
+
+        checked(
+            numerator
+                .checked_mul(PRICE_SCALE)
+                .and_then(|value| value.checked_div(denominator)),
+        )
+    }
+}
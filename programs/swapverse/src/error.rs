@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 pub enum SwapverseError {
     #[msg("User not authorized to use this instruction")]
     UnauthorizedSigner,
-    #[msg("Token mint should be one of the defined stable coin mints")]
+    #[msg("Token mint should be one of the registered stable coin mints")]
     InvalidTokenMint,
     #[msg("Token a mint and token b mint can not be same")]
     SameTokenMints,
@@ -37,4 +37,79 @@ pub enum SwapverseError {
     TokenAmountLimitExceeded,
     #[msg("The output is not enough as asked by user")]
     NotEnoughOutput,
+
+    #[msg("No admin transfer has been proposed")]
+    NoPendingAdmin,
+    #[msg("Token account should be owned by the protocol fee wallet")]
+    InvalidProtocolFeeAccountOwner,
+    #[msg("Minimum fee should not exceed maximum fee and maximum fee should not exceed 10_000 bps")]
+    InvalidFeeBounds,
+    #[msg("Swap fee is outside the bounds allowed by swapverse")]
+    SwapFeeOutOfBounds,
+    #[msg("Swapverse fee is outside the bounds allowed by swapverse")]
+    SwapverseFeeOutOfBounds,
+    #[msg("Swapverse is paused")]
+    SwapversePaused,
+    #[msg("Swap pool is paused")]
+    SwapPoolPaused,
+    #[msg("Swap pool can not move from its current status to the requested one")]
+    InvalidPoolStatusTransition,
+    #[msg("Swap pool has not reached its fill deadline or maturity yet")]
+    SwapPoolNotDueForSettlement,
+    #[msg("Swap pool has matured")]
+    SwapPoolMatured,
+    #[msg("Amplification coefficient is outside the allowed range")]
+    InvalidAmpCoefficient,
+    #[msg("Calculation overflowed")]
+    MathOverflow,
+    #[msg("Stable swap invariant did not converge")]
+    StableSwapDidNotConverge,
+    #[msg("Fixed ratio should be greater than zero")]
+    InvalidFixedRatio,
+    #[msg("Swap pool does not have enough reserves")]
+    InsufficientPoolReserves,
+    #[msg("The input needed is more than allowed by user")]
+    ExcessiveInputAmount,
+    #[msg("Account is not in the layout expected by the migration")]
+    AccountNotInLegacyLayout,
+    #[msg("Profit recorded by the swap pool exceeds its treasury balance")]
+    TreasuryBalanceMismatch,
+    #[msg("Transaction has passed its deadline")]
+    TransactionExpired,
+    #[msg("Route accounts do not form a valid chain of swap pools")]
+    InvalidRoute,
+    #[msg("Swap pool has a flash loan which is not repaid yet")]
+    FlashLoanOutstanding,
+    #[msg("Flash loan should be repaid by a flash_repay later in the same transaction")]
+    FlashRepayMissing,
+    #[msg("Swap pool has no flash loan to repay")]
+    NoFlashLoanToRepay,
+    #[msg("Flash loan amount should be greater than zero")]
+    InvalidFlashLoanAmount,
+    #[msg("End of a twap window should be after its start")]
+    InvalidTwapWindow,
+    #[msg("Swap would move the pool price outside its depeg guard")]
+    PriceOutsideDepegGuard,
+    #[msg("Maximum price deviation should not exceed 10_000 bps")]
+    InvalidPriceDeviation,
+    #[msg("Referral share is more than allowed by swapverse")]
+    ReferralShareTooHigh,
+    #[msg("Referrer token account should be passed for a referral share")]
+    ReferrerAccountMissing,
+    #[msg("Referrer token account should hold the token going out of the swap")]
+    InvalidReferrerTokenAccountMint,
+    #[msg("Maximum referral share should not exceed 10_000 bps")]
+    InvalidMaxReferralShare,
+    #[msg("Multi asset pool should hold between 3 and 8 tokens")]
+    InvalidPoolTokenCount,
+    #[msg("Token mint is used more than once in the pool")]
+    DuplicatePoolTokenMint,
+    #[msg("Token index is outside the tokens of the pool")]
+    InvalidTokenIndex,
+    #[msg("Pool token accounts are missing or do not match the tokens of the pool")]
+    InvalidPoolTokenAccounts,
+    #[msg("Referrer token account should not be owned by the swapper")]
+    SelfReferral,
+    #[msg("Legacy swap pool open for withdrawal cannot be migrated")]
+    LegacySwapPoolOpenForWithdrawal,
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = global_state.pending_admin != Pubkey::default() @ SwapverseError::NoPendingAdmin,
+        constraint = global_state.pending_admin == new_admin.key() @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> AcceptAdmin<'info> {
+    pub fn accept_admin(&mut self) -> Result<()> {
+        self.global_state.admin = self.new_admin.key();
+        self.global_state.pending_admin = Pubkey::default();
+
+        Ok(())
+    }
+}
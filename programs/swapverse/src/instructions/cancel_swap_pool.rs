@@ -0,0 +1,30 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, PoolStatus, SwapPool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelSwapPool<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+}
+
+impl<'info> CancelSwapPool<'info> {
+    pub fn cancel_swap_pool(&mut self) -> Result<()> {
+        // while funding, the amounts to be distributed are exactly the deposits
+        self.swap_pool.transition_to(PoolStatus::Cancelled)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::signed_transfer_tokens;
+use crate::states::{GlobalState, MultiAssetInvestorInfo, MultiAssetPool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(token_index: u8)]
+pub struct ClaimMultiAssetProfit<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_index) @ SwapverseError::InvalidTokenIndex,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), multi_asset_pool.token_mints[token_index as usize].as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+    )]
+    pub multi_asset_pool_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = investor_token_account.owner == investor.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = investor_token_account.mint == multi_asset_pool.token_mints[token_index as usize] @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub investor_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), investor.key().as_ref()],
+        bump,
+    )]
+    pub multi_asset_investor_info: Box<Account<'info, MultiAssetInvestorInfo>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimMultiAssetProfit<'info> {
+    pub fn claim_multi_asset_profit(&mut self, token_index: u8) -> Result<()> {
+        require!(
+            self.multi_asset_pool.status.has_been_activated(),
+            SwapverseError::SwapPoolNotActivated
+        );
+
+        // profit of every token is shared by all the pool shares, whichever token they were
+        // minted for
+        let token_index = token_index as usize;
+        let all_investors_share = self.multi_asset_pool.profits_to_be_distributed[token_index]
+            .checked_sub(self.multi_asset_pool.protocol_share_of_profit(token_index))
+            .unwrap();
+        let investor_pool_shares = self
+            .multi_asset_pool
+            .normalized_pool_shares(&self.multi_asset_investor_info.pool_shares)?;
+        let investor_share = (all_investors_share as u128)
+            .checked_mul(investor_pool_shares as u128)
+            .unwrap()
+            .checked_div(self.multi_asset_pool.total_pool_shares() as u128)
+            .unwrap() as u64;
+        let withdraw_amount = investor_share
+            .checked_sub(self.multi_asset_investor_info.profits_claimed[token_index])
+            .unwrap();
+        require!(withdraw_amount > 0, SwapverseError::WithdrawAmountIsZero);
+
+        self.multi_asset_investor_info.profits_claimed[token_index] =
+            self.multi_asset_investor_info.profits_claimed[token_index]
+                .checked_add(withdraw_amount)
+                .unwrap();
+        self.multi_asset_pool.profits_claimed[token_index] = self.multi_asset_pool.profits_claimed
+            [token_index]
+            .checked_add(withdraw_amount)
+            .unwrap();
+
+        signed_transfer_tokens(
+            withdraw_amount,
+            &mut self.multi_asset_pool_treasury_token_account,
+            &mut self.investor_token_account,
+            &self.signing_authority,
+            &self.token_program,
+            &self.global_state,
+        )?;
+
+        self.multi_asset_pool.reconcile_treasury(
+            token_index,
+            self.multi_asset_pool_treasury_token_account.amount,
+        )
+    }
+}
@@ -106,11 +106,17 @@ pub struct ClaimProfit<'info> {
 impl<'info> ClaimProfit<'info> {
     pub fn claim_profit(&mut self) -> Result<()> {
 
-        require!(self.swap_pool.activated_at != i64::MAX, SwapverseError::SwapPoolNotActivated);
+        require!(self.swap_pool.status.has_been_activated(), SwapverseError::SwapPoolNotActivated);
 
         let is_token_a = self.withdraw_token_mint.key() == self.swap_pool.token_a_mint;
 
-        let investor_pool_share_amount = self.investor_pool_share_token_account.amount;
+        let investor_pool_share_burned_amount = if is_token_a {
+            self.investor_pool_info.pool_share_token_a_burned
+        } else {
+            self.investor_pool_info.pool_share_token_b_burned
+        };
+        let investor_pool_share_amount = self.investor_pool_share_token_account.amount
+            .checked_add(investor_pool_share_burned_amount).unwrap();
         let pool_distribution_token_amount = if is_token_a {
             self.swap_pool.profit_of_token_a_amount_to_be_distributed
         } else {
@@ -123,8 +129,7 @@ impl<'info> ClaimProfit<'info> {
         };
         
         let pool_distribution_token_amount_u128 = pool_distribution_token_amount as u128;
-        let swapverse_share = pool_distribution_token_amount_u128.checked_mul(self.swap_pool.swapverse_fee_percentage as u128)
-                                        .unwrap().checked_div(100).unwrap();
+        let swapverse_share = self.swap_pool.protocol_share_of_profit(is_token_a) as u128;
 
         let all_investors_share_u128 = pool_distribution_token_amount_u128.checked_sub(swapverse_share).unwrap();
 
@@ -149,9 +154,13 @@ impl<'info> ClaimProfit<'info> {
         if is_token_a {
             self.investor_pool_info.profit_for_token_a_withdrawn = self.investor_pool_info.profit_for_token_a_withdrawn
                                                                         .checked_add(withdraw_amount).unwrap();
+            self.swap_pool.profit_of_token_a_claimed = self.swap_pool.profit_of_token_a_claimed
+                                                                        .checked_add(withdraw_amount).unwrap();
         } else {
             self.investor_pool_info.profit_for_token_b_withdrawn = self.investor_pool_info.profit_for_token_b_withdrawn
                                                                         .checked_add(withdraw_amount).unwrap();
+            self.swap_pool.profit_of_token_b_claimed = self.swap_pool.profit_of_token_b_claimed
+                                                                        .checked_add(withdraw_amount).unwrap();
         }
 
         signed_transfer_tokens(
@@ -163,6 +172,11 @@ impl<'info> ClaimProfit<'info> {
             &self.global_state
         )?;
 
-        Ok(())
+        let treasury_balance = if is_token_a {
+            self.swap_pool_treasury_token_a_account.amount
+        } else {
+            self.swap_pool_treasury_token_b_account.amount
+        };
+        self.swap_pool.reconcile_treasury(is_token_a, treasury_balance)
     }
 }
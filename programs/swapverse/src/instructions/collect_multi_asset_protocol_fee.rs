@@ -0,0 +1,79 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::signed_transfer_tokens;
+use crate::states::{GlobalState, MultiAssetPool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(token_index: u8)]
+pub struct CollectMultiAssetProtocolFee<'info> {
+    // anyone can crank the collection, fees always go to the protocol fee wallet
+    pub collector: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_index) @ SwapverseError::InvalidTokenIndex,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), multi_asset_pool.token_mints[token_index as usize].as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+    )]
+    pub multi_asset_pool_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == global_state.protocol_fee_wallet @ SwapverseError::InvalidProtocolFeeAccountOwner,
+        constraint = protocol_fee_token_account.mint == multi_asset_pool.token_mints[token_index as usize] @ SwapverseError::InvalidPoolTokenMint,
+    )]
+    pub protocol_fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CollectMultiAssetProtocolFee<'info> {
+    pub fn collect_multi_asset_protocol_fee(&mut self, token_index: u8) -> Result<()> {
+        // only the not yet collected swapverse share is moved, investor profit stays in the treasury
+        let token_index = token_index as usize;
+        let fee_amount = self.multi_asset_pool.uncollected_protocol_fee(token_index);
+
+        if fee_amount > 0 {
+            self.multi_asset_pool.protocol_fees_collected[token_index] =
+                self.multi_asset_pool.protocol_fees_collected[token_index]
+                    .checked_add(fee_amount)
+                    .unwrap();
+
+            signed_transfer_tokens(
+                fee_amount,
+                &mut self.multi_asset_pool_treasury_token_account,
+                &mut self.protocol_fee_token_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+        }
+
+        self.multi_asset_pool.reconcile_treasury(
+            token_index,
+            self.multi_asset_pool_treasury_token_account.amount,
+        )
+    }
+}
@@ -0,0 +1,128 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::signed_transfer_tokens;
+use crate::states::{GlobalState, SwapPool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    // anyone can crank the collection, fees always go to the protocol fee wallet
+    #[account(mut)]
+    pub collector: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        constraint = token_a_mint.key() == swap_pool.token_a_mint @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_a_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = token_b_mint.key() == swap_pool.token_b_mint @ SwapverseError::InvalidPoolTokenMint,
+        constraint = token_b_mint.key() != token_a_mint.key() @ SwapverseError::SameTokenMints
+    )]
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = collector,
+        seeds = [swap_pool.key().as_ref(), token_a_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_treasury_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = collector,
+        seeds = [swap_pool.key().as_ref(), token_b_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_treasury_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = protocol_fee_token_a_account.owner == global_state.protocol_fee_wallet @ SwapverseError::InvalidProtocolFeeAccountOwner,
+        constraint = protocol_fee_token_a_account.mint == token_a_mint.key() @ SwapverseError::InvalidPoolTokenMint,
+    )]
+    pub protocol_fee_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = protocol_fee_token_b_account.owner == global_state.protocol_fee_wallet @ SwapverseError::InvalidProtocolFeeAccountOwner,
+        constraint = protocol_fee_token_b_account.mint == token_b_mint.key() @ SwapverseError::InvalidPoolTokenMint,
+    )]
+    pub protocol_fee_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CollectProtocolFees<'info> {
+    pub fn collect_protocol_fees(&mut self) -> Result<()> {
+        // only the not yet collected swapverse share is moved, investor profit stays in the treasury
+        let token_a_fee_amount = self.swap_pool.uncollected_protocol_fee(true);
+        let token_b_fee_amount = self.swap_pool.uncollected_protocol_fee(false);
+
+        if token_a_fee_amount > 0 {
+            self.swap_pool.protocol_fee_of_token_a_collected = self
+                .swap_pool
+                .protocol_fee_of_token_a_collected
+                .checked_add(token_a_fee_amount)
+                .unwrap();
+
+            signed_transfer_tokens(
+                token_a_fee_amount,
+                &mut self.swap_pool_treasury_token_a_account,
+                &mut self.protocol_fee_token_a_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+        }
+
+        if token_b_fee_amount > 0 {
+            self.swap_pool.protocol_fee_of_token_b_collected = self
+                .swap_pool
+                .protocol_fee_of_token_b_collected
+                .checked_add(token_b_fee_amount)
+                .unwrap();
+
+            signed_transfer_tokens(
+                token_b_fee_amount,
+                &mut self.swap_pool_treasury_token_b_account,
+                &mut self.protocol_fee_token_b_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+        }
+
+        self.swap_pool
+            .reconcile_treasury(true, self.swap_pool_treasury_token_a_account.amount)?;
+        self.swap_pool
+            .reconcile_treasury(false, self.swap_pool_treasury_token_b_account.amount)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, MultiAssetPool, PoolLifeStart, TokenMintRegistry};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct CreateMultiAssetPool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [global_state.no_of_multi_asset_pools.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        space = size_of::<MultiAssetPool>() + 8,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateMultiAssetPool<'info> {
+    // tokens of the pool are given by their registry and mint accounts in pairs in the
+    // remaining accounts, in the order of their token indexes
+    pub fn create_multi_asset_pool(
+        &mut self,
+        token_accounts: &[AccountInfo<'info>],
+        initial_amount: u64,
+        amp_coefficient: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
+        min_investment_amount: u64,
+        max_days_to_fill: u8,
+        swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
+    ) -> Result<()> {
+        let no_of_tokens = token_accounts.len() / 2;
+        require!(
+            (MIN_MULTI_ASSET_POOL_TOKENS..=MAX_MULTI_ASSET_POOL_TOKENS).contains(&no_of_tokens)
+                && no_of_tokens * 2 == token_accounts.len(),
+            SwapverseError::InvalidPoolTokenCount
+        );
+        require!(
+            (MIN_AMP_COEFFICIENT..=MAX_AMP_COEFFICIENT).contains(&amp_coefficient),
+            SwapverseError::InvalidAmpCoefficient
+        );
+        require!(
+            swap_fee_bps >= self.global_state.min_swap_fee_bps
+                && swap_fee_bps <= self.global_state.max_swap_fee_bps,
+            SwapverseError::SwapFeeOutOfBounds
+        );
+        require!(
+            swapverse_fee_bps >= self.global_state.min_swapverse_fee_bps
+                && swapverse_fee_bps <= self.global_state.max_swapverse_fee_bps,
+            SwapverseError::SwapverseFeeOutOfBounds
+        );
+
+        let multi_asset_pool_key = self.multi_asset_pool.key();
+        let mut token_mints = Vec::with_capacity(no_of_tokens);
+        let mut token_decimals = Vec::with_capacity(no_of_tokens);
+        let mut pool_share_token_mints = Vec::with_capacity(no_of_tokens);
+        for pair in token_accounts.chunks(2) {
+            let registry_info = &pair[0];
+            let token_mint_registry = Account::<TokenMintRegistry>::try_from(registry_info)?;
+            let token_mint = token_mint_registry.mint;
+            let (registry_address, _) = Pubkey::find_program_address(
+                &[token_mint.as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                registry_info.key(),
+                registry_address,
+                SwapverseError::InvalidTokenMint
+            );
+            require_keys_eq!(
+                pair[1].key(),
+                token_mint,
+                SwapverseError::InvalidPoolTokenMint
+            );
+            let mint = Account::<Mint>::try_from(&pair[1])?;
+            require!(
+                !token_mints.contains(&token_mint),
+                SwapverseError::DuplicatePoolTokenMint
+            );
+
+            // created with the other accounts of the token by initialize_multi_asset_pool_token
+            let (pool_share_token_mint, _) = Pubkey::find_program_address(
+                &[
+                    multi_asset_pool_key.as_ref(),
+                    token_mint.as_ref(),
+                    SWAP_POOL_SHARE_TOKEN_SEED.as_bytes(),
+                ],
+                &crate::ID,
+            );
+
+            token_mints.push(token_mint);
+            token_decimals.push(mint.decimals);
+            pool_share_token_mints.push(pool_share_token_mint);
+        }
+
+        let pool_number = self.global_state.no_of_multi_asset_pools;
+        self.global_state.no_of_multi_asset_pools = self
+            .global_state
+            .no_of_multi_asset_pools
+            .checked_add(1)
+            .unwrap();
+
+        self.multi_asset_pool.initialize(
+            pool_number,
+            &token_mints,
+            &token_decimals,
+            &pool_share_token_mints,
+            initial_amount,
+            amp_coefficient,
+            swap_fee_bps,
+            swapverse_fee_bps,
+            min_investment_amount,
+            max_days_to_fill,
+            swap_life_in_days,
+            life_starts_at,
+        )?;
+
+        // every token has to be able to hold the same value once its decimals are accounted for
+        for token_index in 0..no_of_tokens {
+            self.multi_asset_pool.initial_amount_of(token_index)?;
+        }
+
+        Ok(())
+    }
+}
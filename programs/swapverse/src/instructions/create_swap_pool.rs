@@ -1,6 +1,6 @@
 use crate::constants::*;
 use crate::error::SwapverseError;
-use crate::states::{GlobalState, SwapPool};
+use crate::states::{CurveType, GlobalState, PoolLifeStart, SwapPool, TokenMintRegistry};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 use std::mem::size_of;
@@ -33,23 +33,33 @@ pub struct CreateSwapPool<'info> {
     )]
     pub swap_pool: Box<Account<'info, SwapPool>>,
 
-    #[account(
-        constraint = is_mint_valid(global_state.as_ref(), token_a_mint.key()) @ SwapverseError::InvalidTokenMint
-    )]
     pub token_a_mint: Box<Account<'info, Mint>>,
 
     #[account(
-        constraint = is_mint_valid(global_state.as_ref(), token_b_mint.key()) @ SwapverseError::InvalidTokenMint,
         constraint = token_b_mint.key() != token_a_mint.key() @ SwapverseError::SameTokenMints
     )]
     pub token_b_mint: Box<Account<'info, Mint>>,
 
+    #[account(
+        seeds = [token_a_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        constraint = token_a_mint_registry.mint == token_a_mint.key() @ SwapverseError::InvalidTokenMint
+    )]
+    pub token_a_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    #[account(
+        seeds = [token_b_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        constraint = token_b_mint_registry.mint == token_b_mint.key() @ SwapverseError::InvalidTokenMint
+    )]
+    pub token_b_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
     #[account(
         init_if_needed,
         payer = owner,
         seeds = [swap_pool.key().as_ref(), token_a_mint.key().as_ref(), SWAP_POOL_SHARE_TOKEN_SEED.as_bytes()],
         bump,
-        mint::decimals = 6,
+        mint::decimals = token_a_mint.decimals,
         mint::authority = signing_authority,
         mint::freeze_authority = signing_authority
     )]
@@ -60,7 +70,7 @@ pub struct CreateSwapPool<'info> {
         payer = owner,
         seeds = [swap_pool.key().as_ref(), token_b_mint.key().as_ref(), SWAP_POOL_SHARE_TOKEN_SEED.as_bytes()],
         bump,
-        mint::decimals = 6,
+        mint::decimals = token_b_mint.decimals,
         mint::authority = signing_authority,
         mint::freeze_authority = signing_authority
     )]
@@ -70,29 +80,40 @@ pub struct CreateSwapPool<'info> {
     pub system_program: Program<'info, System>,
 }
 
-fn is_mint_valid(global_state: &GlobalState, mint_address: Pubkey) -> bool {
-    for i in 0..5 {
-        if global_state.token_mints[i] == mint_address {
-            return true;
-        }
-    }
-    false
-}
-
 impl<'info> CreateSwapPool<'info> {
     pub fn create_swap_pool(
         &mut self,
         initial_amount_a: u64,
         initial_amount_b: u64,
-        swap_fee_percentage: u8,
-        swapverse_fee_percentage: u8,
+        curve_type: CurveType,
+        amp_coefficient: u64,
+        fixed_ratio: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
         min_investment_amount: u64,
         max_days_to_fill: u8,
         swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
     ) -> Result<()> {
+        if curve_type == CurveType::StableSwap {
+            require!(
+                (MIN_AMP_COEFFICIENT..=MAX_AMP_COEFFICIENT).contains(&amp_coefficient),
+                SwapverseError::InvalidAmpCoefficient
+            );
+        }
+        // the fixed ratio is taken in normalized units, not in raw units of the mints
+        if curve_type == CurveType::FixedRatio {
+            require!(fixed_ratio > 0, SwapverseError::InvalidFixedRatio);
+        }
         require!(
-            initial_amount_a == initial_amount_b,
-            SwapverseError::InitialAmountsNotSame
+            swap_fee_bps >= self.global_state.min_swap_fee_bps
+                && swap_fee_bps <= self.global_state.max_swap_fee_bps,
+            SwapverseError::SwapFeeOutOfBounds
+        );
+        require!(
+            swapverse_fee_bps >= self.global_state.min_swapverse_fee_bps
+                && swapverse_fee_bps <= self.global_state.max_swapverse_fee_bps,
+            SwapverseError::SwapverseFeeOutOfBounds
         );
 
         let pool_number = self.global_state.no_of_swap_pools;
@@ -105,13 +126,28 @@ impl<'info> CreateSwapPool<'info> {
             self.token_b_mint.key(),
             self.pool_share_token_a_mint.key(),
             self.pool_share_token_b_mint.key(),
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
             initial_amount_a,
             initial_amount_b,
-            swap_fee_percentage,
-            swapverse_fee_percentage,
+            curve_type,
+            amp_coefficient,
+            fixed_ratio,
+            swap_fee_bps,
+            swapverse_fee_bps,
             min_investment_amount,
             max_days_to_fill,
             swap_life_in_days,
-        )
+            life_starts_at,
+        )?;
+
+        // both sides have to be worth the same once their decimals are accounted for
+        require!(
+            self.swap_pool.normalize(initial_amount_a, true)?
+                == self.swap_pool.normalize(initial_amount_b, false)?,
+            SwapverseError::InitialAmountsNotSame
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,35 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, TokenMintRegistry};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct DeregisterTokenMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    // existing pools keep working, only new pools can not use the mint
+    #[account(
+        mut,
+        close = admin,
+        seeds = [token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+    )]
+    pub token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+}
+
+impl<'info> DeregisterTokenMint<'info> {
+    pub fn deregister_token_mint(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::signed_transfer_tokens;
+use crate::states::{GlobalState, PoolStatus, SwapPool};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+        constraint = !swap_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = swap_pool.status == PoolStatus::Active @ SwapverseError::SwapPoolNotActivated,
+        constraint = swap_pool.flash_loan_amount == 0 @ SwapverseError::FlashLoanOutstanding,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        constraint = (token_mint.key() == swap_pool.token_a_mint)
+            || (token_mint.key() == swap_pool.token_b_mint) @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = borrower_token_account.owner == borrower.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = borrower_token_account.mint == token_mint.key() @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub borrower_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: address is checked, read through the instructions sysvar helpers
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FlashLoan<'info> {
+    // the loan is only given if a flash_repay of this pool comes later in the same transaction,
+    // the repay fails unless principal and fee come back so the whole transaction fails with it
+    fn check_repay_follows(&self) -> Result<()> {
+        let current_index = load_current_index_checked(&self.instructions_sysvar)? as usize;
+
+        let mut index = current_index + 1;
+        while let Ok(instruction) = load_instruction_at_checked(index, &self.instructions_sysvar)
+        {
+            if instruction.program_id == crate::ID
+                && instruction.data.get(..8) == Some(&crate::instruction::FlashRepay::DISCRIMINATOR)
+                && instruction.accounts.first().map(|account| account.pubkey)
+                    == Some(self.swap_pool.key())
+            {
+                return Ok(());
+            }
+            index += 1;
+        }
+
+        err!(SwapverseError::FlashRepayMissing)
+    }
+
+    pub fn flash_loan(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, SwapverseError::InvalidFlashLoanAmount);
+        require!(
+            self.swap_pool_token_account.amount >= amount,
+            SwapverseError::InsufficientPoolReserves
+        );
+        self.check_repay_follows()?;
+
+        // blocks swaps and settlement of the pool till the loan is repaid
+        self.swap_pool.flash_loan_amount = amount;
+        self.swap_pool.flash_loan_is_token_a =
+            self.token_mint.key() == self.swap_pool.token_a_mint;
+
+        signed_transfer_tokens(
+            amount,
+            &mut self.swap_pool_token_account,
+            &mut self.borrower_token_account,
+            &self.signing_authority,
+            &self.token_program,
+            &self.global_state,
+        )
+    }
+}
@@ -0,0 +1,100 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::transfer_tokens;
+use crate::states::SwapPool;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    // has to stay the first account, flash_loan looks for it to match the repay to the loan
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+        constraint = swap_pool.flash_loan_amount > 0 @ SwapverseError::NoFlashLoanToRepay,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        constraint = (swap_pool.flash_loan_is_token_a && token_mint.key() == swap_pool.token_a_mint)
+            || (!swap_pool.flash_loan_is_token_a && token_mint.key() == swap_pool.token_b_mint)
+            @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        seeds = [swap_pool.key().as_ref(), token_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = borrower_token_account.owner == borrower.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = borrower_token_account.mint == token_mint.key() @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub borrower_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FlashRepay<'info> {
+    pub fn flash_repay(&mut self) -> Result<()> {
+        let loan_amount = self.swap_pool.flash_loan_amount;
+        let fee_amount = self.swap_pool.flash_loan_fee_of(loan_amount);
+        let is_token_a = self.swap_pool.flash_loan_is_token_a;
+
+        require!(
+            self.borrower_token_account.amount >= loan_amount.checked_add(fee_amount).unwrap(),
+            SwapverseError::NotEnoughTokens
+        );
+
+        transfer_tokens(
+            loan_amount,
+            &mut self.borrower_token_account,
+            &mut self.swap_pool_token_account,
+            &self.borrower,
+            &self.token_program,
+        )?;
+
+        transfer_tokens(
+            fee_amount,
+            &mut self.borrower_token_account,
+            &mut self.swap_pool_treasury_token_account,
+            &self.borrower,
+            &self.token_program,
+        )?;
+
+        self.swap_pool.record_profit(is_token_a, fee_amount);
+        self.swap_pool.flash_loan_amount = 0;
+        self.swap_pool.flash_loan_is_token_a = false;
+
+        self.swap_pool
+            .reconcile_treasury(is_token_a, self.swap_pool_treasury_token_account.amount)
+    }
+}
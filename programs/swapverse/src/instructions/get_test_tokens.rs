@@ -1,7 +1,7 @@
 use crate::constants::*;
 use crate::error::SwapverseError;
 use crate::spl_token_utils::{mint_tokens};
-use crate::states::GlobalState;
+use crate::states::{GlobalState, TokenMintRegistry};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -25,11 +25,15 @@ pub struct GetTestTokens<'info> {
     )]
     pub signing_authority: AccountInfo<'info>,
 
+    #[account(mut)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
     #[account(
-        mut,
-        constraint = is_mint_valid(global_state.as_ref(), token_mint.key()) @ SwapverseError::InvalidTokenMint
+        seeds = [token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        constraint = token_mint_registry.mint == token_mint.key() @ SwapverseError::InvalidTokenMint
     )]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
 
     #[account(
         init_if_needed,
@@ -44,15 +48,6 @@ pub struct GetTestTokens<'info> {
     pub system_program: Program<'info, System>,
 }
 
-fn is_mint_valid(global_state: &GlobalState, mint_address: Pubkey) -> bool {
-    for i in 0..5 {
-        if global_state.token_mints[i] == mint_address {
-            return true;
-        }
-    }
-    false
-}
-
 impl<'info> GetTestTokens<'info> {
     pub fn get_test_tokens(&mut self, amount: u64) -> Result<()> {
         require!(
@@ -1,5 +1,5 @@
 use crate::constants::*;
-use crate::states::GlobalState;
+use crate::states::{GlobalState, TokenMintRegistry};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 use std::mem::size_of;
@@ -80,20 +80,65 @@ pub struct InitializeGlobalState<'info> {
     )]
     pub usdh_token_mint: Box<Account<'info, Mint>>,
 
+    // the dev mints are registered right away so that pools can use them on a fresh deploy
+    #[account(
+        init,
+        payer = owner,
+        seeds = [usdc_token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub usdc_token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [usdt_token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub usdt_token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [uxd_token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub uxd_token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [pai_token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub pai_token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [usdh_token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub usdh_token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitializeGlobalState<'info> {
     pub fn initialize_global_state(&mut self, signing_authority_bump: &u8) -> Result<()> {
-        let token_mints = [
-            self.usdc_token_mint.key(),
-            self.usdt_token_mint.key(),
-            self.uxd_token_mint.key(),
-            self.pai_token_mint.key(),
-            self.usdh_token_mint.key(),
-        ];
+        self.usdc_token_mint_registry.initialize(self.usdc_token_mint.key())?;
+        self.usdt_token_mint_registry.initialize(self.usdt_token_mint.key())?;
+        self.uxd_token_mint_registry.initialize(self.uxd_token_mint.key())?;
+        self.pai_token_mint_registry.initialize(self.pai_token_mint.key())?;
+        self.usdh_token_mint_registry.initialize(self.usdh_token_mint.key())?;
+
         self.global_state
-            .initialize(token_mints, signing_authority_bump)
+            .initialize(self.owner.key(), signing_authority_bump)
     }
 }
@@ -0,0 +1,74 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::MultiAssetPool;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+// creates the share mint, pool token account and treasury of one token of a multi asset pool,
+// they do not all fit in the create instruction
+#[derive(Accounts)]
+#[instruction(token_index: u8)]
+pub struct InitializeMultiAssetPoolToken<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_index) @ SwapverseError::InvalidTokenIndex,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        constraint = token_mint.key() == multi_asset_pool.token_mints[token_index as usize] @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [multi_asset_pool.key().as_ref(), token_mint.key().as_ref(), SWAP_POOL_SHARE_TOKEN_SEED.as_bytes()],
+        bump,
+        mint::decimals = token_mint.decimals,
+        mint::authority = signing_authority,
+        mint::freeze_authority = signing_authority
+    )]
+    pub pool_share_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [multi_asset_pool.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = signing_authority,
+    )]
+    pub multi_asset_pool_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [multi_asset_pool.key().as_ref(), token_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_mint,
+        token::authority = signing_authority,
+    )]
+    pub multi_asset_pool_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeMultiAssetPoolToken<'info> {
+    // everything is done by the account constraints
+    pub fn initialize_multi_asset_pool_token(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::{mint_frozen_tokens, transfer_tokens};
+use crate::states::{GlobalState, MultiAssetInvestorInfo, MultiAssetPool, PoolStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use std::cmp::min;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+#[instruction(token_index: u8, amount: u64)]
+pub struct InvestMultiAssetPool<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_index) @ SwapverseError::InvalidTokenIndex,
+        constraint = !multi_asset_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = multi_asset_pool.status == PoolStatus::Funding @ SwapverseError::SwapPoolNotOpenForInvestment,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        constraint = token_mint.key() == multi_asset_pool.token_mints[token_index as usize] @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub multi_asset_pool_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = investor_token_account.owner == investor.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = investor_token_account.mint == token_mint.key() @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub investor_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_share_token_mint.key() == multi_asset_pool.pool_share_token_mints[token_index as usize] @ SwapverseError::InvalidPoolShareTokenMint
+    )]
+    pub pool_share_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = investor,
+        associated_token::mint = pool_share_token_mint,
+        associated_token::authority = investor
+    )]
+    pub investor_pool_share_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = investor,
+        seeds = [multi_asset_pool.key().as_ref(), investor.key().as_ref()],
+        bump,
+        space = size_of::<MultiAssetInvestorInfo>() + 8,
+    )]
+    pub multi_asset_investor_info: Box<Account<'info, MultiAssetInvestorInfo>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InvestMultiAssetPool<'info> {
+    pub fn invest_multi_asset_pool(&mut self, token_index: u8, amount: u64) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp <= self.multi_asset_pool.fill_deadline()?,
+            SwapverseError::SwapPoolNotOpenForInvestment
+        );
+
+        let token_index = token_index as usize;
+        require!(
+            self.multi_asset_pool.normalize(amount, token_index)?
+                >= self.multi_asset_pool.min_investment_amount,
+            SwapverseError::InsufficientAmount
+        );
+
+        let amount_remaining = self
+            .multi_asset_pool
+            .initial_amount_of(token_index)?
+            .checked_sub(self.multi_asset_pool.reserves[token_index])
+            .unwrap();
+        let deposit_amount = min(amount, amount_remaining);
+        require!(deposit_amount > 0, SwapverseError::DepositAmountIsZero);
+
+        self.multi_asset_pool.reserves[token_index] = self.multi_asset_pool.reserves[token_index]
+            .checked_add(deposit_amount)
+            .unwrap();
+        self.multi_asset_pool.pool_shares_outstanding = self
+            .multi_asset_pool
+            .pool_shares_outstanding
+            .checked_add(deposit_amount)
+            .unwrap();
+
+        self.multi_asset_investor_info.investor = self.investor.key();
+        self.multi_asset_investor_info.multi_asset_pool = self.multi_asset_pool.key();
+        self.multi_asset_investor_info.pool_shares[token_index] =
+            self.multi_asset_investor_info.pool_shares[token_index]
+                .checked_add(deposit_amount)
+                .unwrap();
+
+        transfer_tokens(
+            deposit_amount,
+            &mut self.investor_token_account,
+            &mut self.multi_asset_pool_token_account,
+            &self.investor,
+            &self.token_program,
+        )?;
+
+        mint_frozen_tokens(
+            deposit_amount,
+            &self.token_program,
+            &mut self.investor_pool_share_token_account,
+            &self.signing_authority,
+            &mut self.pool_share_token_mint,
+            &self.global_state,
+        )?;
+
+        if self.multi_asset_pool.is_filled()? {
+            self.multi_asset_pool.transition_to(PoolStatus::Active)?;
+        }
+
+        Ok(())
+    }
+}
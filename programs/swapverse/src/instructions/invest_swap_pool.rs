@@ -4,7 +4,7 @@ use std::mem::size_of;
 use crate::constants::*;
 use crate::error::SwapverseError;
 use crate::spl_token_utils::{transfer_tokens, mint_frozen_tokens};
-use crate::states::{SwapPool, GlobalState, InvestorPoolInfo};
+use crate::states::{SwapPool, GlobalState, InvestorPoolInfo, PoolStatus};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
@@ -19,6 +19,7 @@ pub struct InvestSwapPool<'info> {
         mut,
         seeds = [GLOBAL_STATE_SEED.as_bytes()],
         bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
     )]
     pub global_state: Box<Account<'info, GlobalState>>,
 
@@ -33,7 +34,8 @@ pub struct InvestSwapPool<'info> {
         mut,
         seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
         bump,
-        constraint = swap_pool.open_for_investment == true @ SwapverseError::SwapPoolNotOpenForInvestment,
+        constraint = !swap_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = swap_pool.status == PoolStatus::Funding @ SwapverseError::SwapPoolNotOpenForInvestment,
         constraint = amount >= swap_pool.min_investment_amount @ SwapverseError::InsufficientAmount
     )]
     pub swap_pool: Box<Account<'info, SwapPool>>,
@@ -92,6 +94,11 @@ pub struct InvestSwapPool<'info> {
 
 impl<'info> InvestSwapPool<'info> {
     pub fn invest_swap_pool(&mut self, amount: u64) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp <= self.swap_pool.fill_deadline()?,
+            SwapverseError::SwapPoolNotOpenForInvestment
+        );
+
         let pool_token_amount = self.swap_pool_token_account.amount;
         let is_token_a = self.token_mint.key() == self.swap_pool.token_a_mint;
         let pool_token_initial_amount = if is_token_a {
@@ -103,14 +110,6 @@ impl<'info> InvestSwapPool<'info> {
             .checked_sub(pool_token_amount)
             .unwrap();
 
-        if (self.swap_pool.token_a_amount_to_be_distributed == self.swap_pool.initial_amount_a) 
-            && (self.swap_pool.token_b_amount_to_be_distributed == self.swap_pool.initial_amount_b) {
-                self.swap_pool.open_for_investment = false;
-                self.swap_pool.active_for_swap = true;
-                self.swap_pool.activated_at =  Clock::get()?.unix_timestamp;
-        }
-        require!(self.swap_pool.open_for_investment, SwapverseError::SwapPoolNotOpenForInvestment);
-
         let deposit_amount = min(amount, amount_remaining);
         require!(deposit_amount > 0, SwapverseError::DepositAmountIsZero);
 
@@ -142,9 +141,7 @@ impl<'info> InvestSwapPool<'info> {
 
         if (self.swap_pool.token_a_amount_to_be_distributed == self.swap_pool.initial_amount_a) 
             && (self.swap_pool.token_b_amount_to_be_distributed == self.swap_pool.initial_amount_b) {
-                self.swap_pool.open_for_investment = false;
-                self.swap_pool.active_for_swap = true;
-                self.swap_pool.activated_at =  Clock::get()?.unix_timestamp;
+                self.swap_pool.transition_to(PoolStatus::Active)?;
         }
 
         Ok(())
@@ -0,0 +1,51 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::migration_utils::{read_legacy_account, write_migrated_account};
+use crate::program::Swapverse;
+use crate::states::{GlobalState, LegacyGlobalState};
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+// legacy global state has no admin, the upgrade authority of the program migrates it and
+// becomes the admin
+#[derive(Accounts)]
+pub struct MigrateGlobalState<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: still in the legacy layout, checked while it is read
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub program: Program<'info, Swapverse>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateGlobalState<'info> {
+    pub fn migrate_global_state(&mut self) -> Result<()> {
+        let global_state_info = self.global_state.to_account_info();
+        let legacy_global_state: LegacyGlobalState =
+            read_legacy_account::<GlobalState, _>(&global_state_info, LegacyGlobalState::SPACE)?;
+
+        write_migrated_account(
+            &global_state_info,
+            &legacy_global_state.migrate(self.admin.key()),
+            size_of::<GlobalState>() + 8,
+            &self.admin,
+            &self.system_program,
+        )
+    }
+}
@@ -0,0 +1,39 @@
+use crate::migration_utils::{read_legacy_account, write_migrated_account};
+use crate::states::{InvestorPoolInfo, LegacyInvestorPoolInfo};
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+// only changes the layout of the account, so anyone paying for the extra rent can run it
+#[derive(Accounts)]
+pub struct MigrateInvestorPoolInfo<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: still in the legacy layout, checked while it is read
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub investor_pool_info: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateInvestorPoolInfo<'info> {
+    pub fn migrate_investor_pool_info(&mut self) -> Result<()> {
+        let investor_pool_info = self.investor_pool_info.to_account_info();
+        let legacy_investor_pool_info: LegacyInvestorPoolInfo =
+            read_legacy_account::<InvestorPoolInfo, _>(
+                &investor_pool_info,
+                LegacyInvestorPoolInfo::SPACE,
+            )?;
+
+        write_migrated_account(
+            &investor_pool_info,
+            &legacy_investor_pool_info.migrate(),
+            size_of::<InvestorPoolInfo>() + 8,
+            &self.payer,
+            &self.system_program,
+        )
+    }
+}
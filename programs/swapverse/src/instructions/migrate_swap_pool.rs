@@ -0,0 +1,99 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::migration_utils::{read_legacy_account, write_migrated_account};
+use crate::states::{GlobalState, LegacySwapPool, SwapPool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct MigrateSwapPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: still in the legacy layout, checked while it is read
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub swap_pool: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: only read for its signing authority address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    // legacy pools created their treasuries on the first swap paying a fee in that token
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [swap_pool.key().as_ref(), token_a_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_treasury_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [swap_pool.key().as_ref(), token_b_mint.key().as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_treasury_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateSwapPool<'info> {
+    pub fn migrate_swap_pool(&mut self) -> Result<()> {
+        let swap_pool_info = self.swap_pool.to_account_info();
+        let legacy_swap_pool: LegacySwapPool =
+            read_legacy_account::<SwapPool, _>(&swap_pool_info, LegacySwapPool::SPACE)?;
+
+        let mut swap_pool = legacy_swap_pool.migrate(
+            self.swap_pool_treasury_token_a_account.amount,
+            self.swap_pool_treasury_token_b_account.amount,
+        )?;
+        // prices before the migration were not recorded, accumulators start from here
+        swap_pool.last_price_update_at = Clock::get()?.unix_timestamp;
+
+        // legacy pools did not store the decimals of their mints
+        require_keys_eq!(
+            self.token_a_mint.key(),
+            swap_pool.token_a_mint,
+            SwapverseError::InvalidPoolTokenMint
+        );
+        require_keys_eq!(
+            self.token_b_mint.key(),
+            swap_pool.token_b_mint,
+            SwapverseError::InvalidPoolTokenMint
+        );
+        swap_pool.token_a_decimals = self.token_a_mint.decimals;
+        swap_pool.token_b_decimals = self.token_b_mint.decimals;
+
+        write_migrated_account(
+            &swap_pool_info,
+            &swap_pool,
+            size_of::<SwapPool>() + 8,
+            &self.admin,
+            &self.system_program,
+        )
+    }
+}
@@ -5,6 +5,36 @@ mod withdraw_swap_pool;
 mod get_test_tokens;
 mod swap_tokens;
 mod claim_profit;
+mod propose_admin;
+mod accept_admin;
+mod register_token_mint;
+mod deregister_token_mint;
+mod set_protocol_fee_wallet;
+mod collect_protocol_fees;
+mod update_fee_config;
+mod set_global_pause;
+mod set_pool_pause;
+mod cancel_swap_pool;
+mod settle_pool;
+mod migrate_global_state;
+mod migrate_swap_pool;
+mod migrate_investor_pool_info;
+mod swap_route;
+mod quote_swap;
+mod flash_loan;
+mod flash_repay;
+mod set_pool_depeg_guard;
+mod set_max_referral_share;
+mod set_dynamic_fee;
+mod create_multi_asset_pool;
+mod initialize_multi_asset_pool_token;
+mod invest_multi_asset_pool;
+mod swap_multi_asset_pool;
+mod settle_multi_asset_pool;
+mod withdraw_multi_asset_pool;
+mod claim_multi_asset_profit;
+mod collect_multi_asset_protocol_fee;
+mod set_multi_asset_pool_pause;
 
 pub use initialize_global_state::*;
 pub use create_swap_pool::*;
@@ -13,3 +43,33 @@ pub use withdraw_swap_pool::*;
 pub use get_test_tokens::*;
 pub use swap_tokens::*;
 pub use claim_profit::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use register_token_mint::*;
+pub use deregister_token_mint::*;
+pub use set_protocol_fee_wallet::*;
+pub use collect_protocol_fees::*;
+pub use update_fee_config::*;
+pub use set_global_pause::*;
+pub use set_pool_pause::*;
+pub use cancel_swap_pool::*;
+pub use settle_pool::*;
+pub use migrate_global_state::*;
+pub use migrate_swap_pool::*;
+pub use migrate_investor_pool_info::*;
+pub use swap_route::*;
+pub use quote_swap::*;
+pub use flash_loan::*;
+pub use flash_repay::*;
+pub use set_pool_depeg_guard::*;
+pub use set_max_referral_share::*;
+pub use set_dynamic_fee::*;
+pub use create_multi_asset_pool::*;
+pub use initialize_multi_asset_pool_token::*;
+pub use invest_multi_asset_pool::*;
+pub use swap_multi_asset_pool::*;
+pub use settle_multi_asset_pool::*;
+pub use withdraw_multi_asset_pool::*;
+pub use claim_multi_asset_profit::*;
+pub use collect_multi_asset_protocol_fee::*;
+pub use set_multi_asset_pool_pause::*;
@@ -0,0 +1,25 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> ProposeAdmin<'info> {
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        self.global_state.pending_admin = new_admin;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, PoolStatus, SwapPool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+    // amount the user would receive, fee already levied
+    pub amount_out: u64,
+    // amount of token out which would go to the pool treasury
+    pub fee: u64,
+    // how much worse than the spot price the swap would be executed before the fee
+    pub price_impact_bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+        constraint = !swap_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = swap_pool.status == PoolStatus::Active @ SwapverseError::SwapPoolNotActivated,
+        constraint = swap_pool.flash_loan_amount == 0 @ SwapverseError::FlashLoanOutstanding,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        seeds = [swap_pool.key().as_ref(), swap_pool.token_a_mint.as_ref()],
+        bump,
+    )]
+    pub swap_pool_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [swap_pool.key().as_ref(), swap_pool.token_b_mint.as_ref()],
+        bump,
+    )]
+    pub swap_pool_token_b_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> QuoteSwap<'info> {
+    // runs the pricing of swap_token without moving any tokens
+    pub fn quote_swap(&self, amount: u64, is_token_in_token_a: bool) -> Result<SwapQuote> {
+        self.swap_pool
+            .check_swap_open(Clock::get()?.unix_timestamp, None)?;
+
+        let (reserve_in, reserve_out) = if is_token_in_token_a {
+            (
+                self.swap_pool_token_a_account.amount,
+                self.swap_pool_token_b_account.amount,
+            )
+        } else {
+            (
+                self.swap_pool_token_b_account.amount,
+                self.swap_pool_token_a_account.amount,
+            )
+        };
+
+        let invariant = self
+            .swap_pool
+            .invariant(reserve_in, reserve_out, is_token_in_token_a)?;
+        let (user_share, treasury_share) = self.swap_pool.swap_exact_in(
+            amount,
+            reserve_in,
+            reserve_out,
+            is_token_in_token_a,
+            invariant,
+        )?;
+        self.swap_pool.check_depeg_guard(
+            reserve_in,
+            reserve_out,
+            amount,
+            user_share.checked_add(treasury_share).unwrap(),
+            is_token_in_token_a,
+            invariant,
+        )?;
+        let price_impact_bps = self.swap_pool.price_impact_bps(
+            amount,
+            user_share.checked_add(treasury_share).unwrap(),
+            reserve_in,
+            reserve_out,
+            is_token_in_token_a,
+            invariant,
+        )?;
+
+        Ok(SwapQuote {
+            amount_out: user_share,
+            fee: treasury_share,
+            price_impact_bps,
+        })
+    }
+}
@@ -0,0 +1,38 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, TokenMintRegistry};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct RegisterTokenMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [token_mint.key().as_ref(), TOKEN_MINT_REGISTRY_SEED.as_bytes()],
+        bump,
+        space = size_of::<TokenMintRegistry>() + 8,
+    )]
+    pub token_mint_registry: Box<Account<'info, TokenMintRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterTokenMint<'info> {
+    pub fn register_token_mint(&mut self) -> Result<()> {
+        self.token_mint_registry.initialize(self.token_mint.key())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, SwapPool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDynamicFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+}
+
+impl<'info> SetDynamicFee<'info> {
+    pub fn set_dynamic_fee(
+        &mut self,
+        dynamic_fee_enabled: bool,
+        min_dynamic_fee_bps: u16,
+        max_dynamic_fee_bps: u16,
+    ) -> Result<()> {
+        // the whole output may not go to the fee
+        require!(
+            min_dynamic_fee_bps <= max_dynamic_fee_bps
+                && (max_dynamic_fee_bps as u64) < BPS_DENOMINATOR,
+            SwapverseError::InvalidFeeBounds
+        );
+        require!(
+            min_dynamic_fee_bps >= self.global_state.min_swap_fee_bps
+                && max_dynamic_fee_bps <= self.global_state.max_swap_fee_bps,
+            SwapverseError::SwapFeeOutOfBounds
+        );
+
+        self.swap_pool.dynamic_fee_enabled = dynamic_fee_enabled;
+        self.swap_pool.min_dynamic_fee_bps = min_dynamic_fee_bps;
+        self.swap_pool.max_dynamic_fee_bps = max_dynamic_fee_bps;
+
+        Ok(())
+    }
+}
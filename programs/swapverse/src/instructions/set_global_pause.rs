@@ -0,0 +1,25 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> SetGlobalPause<'info> {
+    pub fn set_global_pause(&mut self, paused: bool) -> Result<()> {
+        self.global_state.paused = paused;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMaxReferralShare<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> SetMaxReferralShare<'info> {
+    pub fn set_max_referral_share(&mut self, max_referral_share_bps: u16) -> Result<()> {
+        require!(
+            max_referral_share_bps as u64 <= BPS_DENOMINATOR,
+            SwapverseError::InvalidMaxReferralShare
+        );
+        self.global_state.max_referral_share_bps = max_referral_share_bps;
+
+        Ok(())
+    }
+}
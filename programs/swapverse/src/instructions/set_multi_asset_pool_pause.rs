@@ -0,0 +1,31 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, MultiAssetPool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMultiAssetPoolPause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+}
+
+impl<'info> SetMultiAssetPoolPause<'info> {
+    pub fn set_multi_asset_pool_pause(&mut self, paused: bool) -> Result<()> {
+        self.multi_asset_pool.paused = paused;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, SwapPool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolDepegGuard<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+}
+
+impl<'info> SetPoolDepegGuard<'info> {
+    pub fn set_pool_depeg_guard(&mut self, max_price_deviation_bps: u16) -> Result<()> {
+        require!(
+            max_price_deviation_bps as u64 <= BPS_DENOMINATOR,
+            SwapverseError::InvalidPriceDeviation
+        );
+        self.swap_pool.max_price_deviation_bps = max_price_deviation_bps;
+
+        Ok(())
+    }
+}
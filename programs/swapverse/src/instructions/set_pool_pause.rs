@@ -0,0 +1,31 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{GlobalState, SwapPool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolPause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+}
+
+impl<'info> SetPoolPause<'info> {
+    pub fn set_pool_pause(&mut self, paused: bool) -> Result<()> {
+        self.swap_pool.paused = paused;
+
+        Ok(())
+    }
+}
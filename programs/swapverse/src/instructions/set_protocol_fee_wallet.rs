@@ -0,0 +1,25 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetProtocolFeeWallet<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> SetProtocolFeeWallet<'info> {
+    pub fn set_protocol_fee_wallet(&mut self, protocol_fee_wallet: Pubkey) -> Result<()> {
+        self.global_state.protocol_fee_wallet = protocol_fee_wallet;
+
+        Ok(())
+    }
+}
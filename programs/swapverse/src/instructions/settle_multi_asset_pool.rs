@@ -0,0 +1,26 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::MultiAssetPool;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SettleMultiAssetPool<'info> {
+    // permissionless, any keeper can settle a pool which is due
+    pub settler: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+}
+
+impl<'info> SettleMultiAssetPool<'info> {
+    pub fn settle_multi_asset_pool(&mut self) -> Result<()> {
+        let settled = self.multi_asset_pool.settle(Clock::get()?.unix_timestamp)?;
+        require!(settled, SwapverseError::SwapPoolNotDueForSettlement);
+
+        Ok(())
+    }
+}
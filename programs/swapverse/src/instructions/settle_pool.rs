@@ -0,0 +1,75 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::SwapPool;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SettlePool<'info> {
+    // permissionless, any keeper can settle a pool which is due
+    #[account(mut)]
+    pub settler: Signer<'info>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
+        bump,
+        constraint = swap_pool.flash_loan_amount == 0 @ SwapverseError::FlashLoanOutstanding,
+    )]
+    pub swap_pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        constraint = token_a_mint.key() == swap_pool.token_a_mint @ SwapverseError::InvalidPoolTokenMint
+    )]
+    pub token_a_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = token_b_mint.key() == swap_pool.token_b_mint @ SwapverseError::InvalidPoolTokenMint,
+        constraint = token_b_mint.key() != token_a_mint.key() @ SwapverseError::SameTokenMints
+    )]
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // an unfilled pool may not have received any deposit of one of the tokens
+    #[account(
+        init_if_needed,
+        payer = settler,
+        seeds = [swap_pool.key().as_ref(), token_a_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = settler,
+        seeds = [swap_pool.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = signing_authority,
+    )]
+    pub swap_pool_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettlePool<'info> {
+    pub fn settle_pool(&mut self) -> Result<()> {
+        let settled = self.swap_pool.settle(
+            Clock::get()?.unix_timestamp,
+            self.swap_pool_token_a_account.amount,
+            self.swap_pool_token_b_account.amount,
+        )?;
+        require!(settled, SwapverseError::SwapPoolNotDueForSettlement);
+
+        Ok(())
+    }
+}
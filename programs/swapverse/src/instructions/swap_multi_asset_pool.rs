@@ -0,0 +1,143 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::{signed_transfer_tokens, transfer_tokens};
+use crate::states::{GlobalState, MultiAssetPool, PoolStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(token_in_index: u8, token_out_index: u8)]
+pub struct SwapMultiAssetPool<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_in_index) @ SwapverseError::InvalidTokenIndex,
+        constraint = multi_asset_pool.is_token_index(token_out_index) @ SwapverseError::InvalidTokenIndex,
+        constraint = token_in_index != token_out_index @ SwapverseError::SameTokenMints,
+        constraint = !multi_asset_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = multi_asset_pool.status == PoolStatus::Active @ SwapverseError::SwapPoolNotActivated,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        mut,
+        constraint = user_token_in_account.owner == user.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = user_token_in_account.mint == multi_asset_pool.token_mints[token_in_index as usize] @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub user_token_in_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_out_account.owner == user.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+        constraint = user_token_out_account.mint == multi_asset_pool.token_mints[token_out_index as usize] @ SwapverseError::InvalidInvestorTokenAccountMint,
+    )]
+    pub user_token_out_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), multi_asset_pool.token_mints[token_in_index as usize].as_ref()],
+        bump,
+    )]
+    pub multi_asset_pool_token_in_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), multi_asset_pool.token_mints[token_out_index as usize].as_ref()],
+        bump,
+    )]
+    pub multi_asset_pool_token_out_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.key().as_ref(), multi_asset_pool.token_mints[token_out_index as usize].as_ref(), SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes()],
+        bump,
+    )]
+    pub multi_asset_pool_treasury_token_out_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> SwapMultiAssetPool<'info> {
+    pub fn swap_multi_asset_pool(
+        &mut self,
+        token_in_index: u8,
+        token_out_index: u8,
+        amount: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        self.multi_asset_pool
+            .check_swap_open(Clock::get()?.unix_timestamp, deadline)?;
+        require!(
+            self.user_token_in_account.amount >= amount,
+            SwapverseError::NotEnoughTokens
+        );
+
+        let (token_in_index, token_out_index) = (token_in_index as usize, token_out_index as usize);
+        let (user_share, treasury_share) =
+            self.multi_asset_pool
+                .swap_exact_in(token_in_index, token_out_index, amount)?;
+
+        require!(
+            user_share >= min_amount_out,
+            SwapverseError::NotEnoughOutput
+        );
+
+        self.multi_asset_pool.record_swap(
+            token_in_index,
+            token_out_index,
+            amount,
+            user_share,
+            treasury_share,
+        );
+
+        signed_transfer_tokens(
+            user_share,
+            &mut self.multi_asset_pool_token_out_account,
+            &mut self.user_token_out_account,
+            &self.signing_authority,
+            &self.token_program,
+            &self.global_state,
+        )?;
+
+        signed_transfer_tokens(
+            treasury_share,
+            &mut self.multi_asset_pool_token_out_account,
+            &mut self.multi_asset_pool_treasury_token_out_account,
+            &self.signing_authority,
+            &self.token_program,
+            &self.global_state,
+        )?;
+
+        transfer_tokens(
+            amount,
+            &mut self.user_token_in_account,
+            &mut self.multi_asset_pool_token_in_account,
+            &self.user,
+            &self.token_program,
+        )?;
+
+        self.multi_asset_pool.reconcile_treasury(
+            token_out_index,
+            self.multi_asset_pool_treasury_token_out_account.amount,
+        )
+    }
+}
@@ -0,0 +1,218 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::{signed_transfer_tokens, transfer_tokens};
+use crate::states::{GlobalState, PoolStatus, SwapPool};
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsExit;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    // token given by the user to the first pool of the route
+    #[account(
+        mut,
+        constraint = user_token_in_account.owner == user.key() @ SwapverseError::InvalidInvestorTokenAccountOwner,
+    )]
+    pub user_token_in_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+fn pool_token_account_address(swap_pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[swap_pool.as_ref(), mint.as_ref()], &crate::ID).0
+}
+
+fn pool_treasury_account_address(swap_pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            swap_pool.as_ref(),
+            mint.as_ref(),
+            SWAP_POOL_TREASURY_ACCOUNT_SEED.as_bytes(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+impl<'info> SwapRoute<'info> {
+    // every hop swaps the whole output of the previous one, the output of a hop is sent to
+    // the user token out account of that hop which is the token in account of the next one
+    pub fn swap_route(
+        &mut self,
+        hop_accounts: &[AccountInfo<'info>],
+        amount: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        let no_of_hops = hop_accounts.len() / ACCOUNTS_PER_ROUTE_HOP;
+        require!(
+            no_of_hops > 0
+                && no_of_hops <= MAX_ROUTE_HOPS
+                && no_of_hops * ACCOUNTS_PER_ROUTE_HOP == hop_accounts.len(),
+            SwapverseError::InvalidRoute
+        );
+        require!(
+            self.user_token_in_account.amount >= amount,
+            SwapverseError::NotEnoughTokens
+        );
+
+        let time_now = Clock::get()?.unix_timestamp;
+        let mut user_token_in_account = self.user_token_in_account.clone();
+        let mut amount_in = amount;
+
+        for hop in hop_accounts.chunks(ACCOUNTS_PER_ROUTE_HOP) {
+            let mut swap_pool = Account::<SwapPool>::try_from(&hop[0])?;
+            require!(!swap_pool.paused, SwapverseError::SwapPoolPaused);
+            require!(
+                swap_pool.status == PoolStatus::Active,
+                SwapverseError::SwapPoolNotActivated
+            );
+            require!(
+                swap_pool.flash_loan_amount == 0,
+                SwapverseError::FlashLoanOutstanding
+            );
+            swap_pool.check_swap_open(time_now, deadline)?;
+
+            let token_in_mint = user_token_in_account.mint;
+            let is_token_in_token_a = if token_in_mint == swap_pool.token_a_mint {
+                true
+            } else {
+                require_keys_eq!(
+                    token_in_mint,
+                    swap_pool.token_b_mint,
+                    SwapverseError::InvalidPoolTokenMint
+                );
+                false
+            };
+            let token_out_mint = if is_token_in_token_a {
+                swap_pool.token_b_mint
+            } else {
+                swap_pool.token_a_mint
+            };
+
+            require_keys_eq!(
+                hop[1].key(),
+                pool_token_account_address(&swap_pool.key(), &token_in_mint),
+                SwapverseError::InvalidRoute
+            );
+            require_keys_eq!(
+                hop[2].key(),
+                pool_token_account_address(&swap_pool.key(), &token_out_mint),
+                SwapverseError::InvalidRoute
+            );
+            require_keys_eq!(
+                hop[3].key(),
+                pool_treasury_account_address(&swap_pool.key(), &token_out_mint),
+                SwapverseError::InvalidRoute
+            );
+            let mut swap_pool_token_in_account = Account::<TokenAccount>::try_from(&hop[1])?;
+            let mut swap_pool_token_out_account = Account::<TokenAccount>::try_from(&hop[2])?;
+            let mut swap_pool_treasury_token_out_account =
+                Account::<TokenAccount>::try_from(&hop[3])?;
+
+            let mut user_token_out_account = Box::new(Account::<TokenAccount>::try_from(&hop[4])?);
+            require_keys_eq!(
+                user_token_out_account.owner,
+                self.user.key(),
+                SwapverseError::InvalidInvestorTokenAccountOwner
+            );
+            require_keys_eq!(
+                user_token_out_account.mint,
+                token_out_mint,
+                SwapverseError::InvalidInvestorTokenAccountMint
+            );
+
+            let invariant = swap_pool.invariant(
+                swap_pool_token_in_account.amount,
+                swap_pool_token_out_account.amount,
+                is_token_in_token_a,
+            )?;
+            let (user_share, treasury_share) = swap_pool.swap_exact_in(
+                amount_in,
+                swap_pool_token_in_account.amount,
+                swap_pool_token_out_account.amount,
+                is_token_in_token_a,
+                invariant,
+            )?;
+            swap_pool.check_depeg_guard(
+                swap_pool_token_in_account.amount,
+                swap_pool_token_out_account.amount,
+                amount_in,
+                user_share.checked_add(treasury_share).unwrap(),
+                is_token_in_token_a,
+                invariant,
+            )?;
+
+            let (reserve_a, reserve_b) = if is_token_in_token_a {
+                (
+                    swap_pool_token_in_account.amount,
+                    swap_pool_token_out_account.amount,
+                )
+            } else {
+                (
+                    swap_pool_token_out_account.amount,
+                    swap_pool_token_in_account.amount,
+                )
+            };
+            swap_pool.update_price_accumulators(time_now, reserve_a, reserve_b, invariant)?;
+            swap_pool.record_swap_fee(is_token_in_token_a, treasury_share);
+
+            signed_transfer_tokens(
+                user_share,
+                &mut swap_pool_token_out_account,
+                &mut user_token_out_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+
+            signed_transfer_tokens(
+                treasury_share,
+                &mut swap_pool_token_out_account,
+                &mut swap_pool_treasury_token_out_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+
+            transfer_tokens(
+                amount_in,
+                &mut user_token_in_account,
+                &mut swap_pool_token_in_account,
+                &self.user,
+                &self.token_program,
+            )?;
+
+            swap_pool.reconcile_treasury(
+                !is_token_in_token_a,
+                swap_pool_treasury_token_out_account.amount,
+            )?;
+            // the pool is not part of the instruction accounts, so it has to be written back here
+            swap_pool.exit(&crate::ID)?;
+
+            user_token_in_account = user_token_out_account;
+            amount_in = user_share;
+        }
+
+        require!(amount_in >= min_amount_out, SwapverseError::NotEnoughOutput);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::constants::*;
 use crate::error::SwapverseError;
 use crate::spl_token_utils::{signed_transfer_tokens, transfer_tokens};
-use crate::states::{GlobalState, SwapPool};
+use crate::states::{GlobalState, PoolStatus, SwapPool};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
@@ -14,6 +14,7 @@ pub struct SwapToken<'info> {
         mut,
         seeds = [GLOBAL_STATE_SEED.as_bytes()],
         bump,
+        constraint = !global_state.paused @ SwapverseError::SwapversePaused,
     )]
     pub global_state: Box<Account<'info, GlobalState>>,
 
@@ -28,6 +29,9 @@ pub struct SwapToken<'info> {
         mut,
         seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
         bump,
+        constraint = !swap_pool.paused @ SwapverseError::SwapPoolPaused,
+        constraint = swap_pool.status == PoolStatus::Active @ SwapverseError::SwapPoolNotActivated,
+        constraint = swap_pool.flash_loan_amount == 0 @ SwapverseError::FlashLoanOutstanding,
     )]
     pub swap_pool: Box<Account<'info, SwapPool>>,
 
@@ -99,59 +103,63 @@ pub struct SwapToken<'info> {
 }
 
 impl<'info> SwapToken<'info> {
-    pub fn swap_token(
+    fn check_swap_open(&self, deadline: Option<i64>) -> Result<()> {
+        self.swap_pool
+            .check_swap_open(Clock::get()?.unix_timestamp, deadline)
+    }
+
+    // user balance of token in, pool reserve of token in and pool reserve of token out
+    fn swap_balances(&self, is_token_in_token_a: bool) -> (u64, u64, u64) {
+        if is_token_in_token_a {
+            (
+                self.user_token_a_account.amount,
+                self.swap_pool_token_a_account.amount,
+                self.swap_pool_token_b_account.amount,
+            )
+        } else {
+            (
+                self.user_token_b_account.amount,
+                self.swap_pool_token_b_account.amount,
+                self.swap_pool_token_a_account.amount,
+            )
+        }
+    }
+
+    fn execute_swap(
         &mut self,
-        amount: u64,
-        min_amount_out: u64,
+        amount_in: u64,
+        user_amount_out: u64,
+        treasury_amount: u64,
         is_token_in_token_a: bool,
+        invariant: Option<u128>,
+        referral: Option<(Account<'info, TokenAccount>, u64)>,
     ) -> Result<()> {
-        let initial_amount_a_u128 = self.swap_pool.initial_amount_a as u128;
-        let initial_amount_b_u128 = self.swap_pool.initial_amount_b as u128;
+        let (_, reserve_in, reserve_out) = self.swap_balances(is_token_in_token_a);
+        self.swap_pool.check_depeg_guard(
+            reserve_in,
+            reserve_out,
+            amount_in,
+            user_amount_out.checked_add(treasury_amount).unwrap(),
+            is_token_in_token_a,
+            invariant,
+        )?;
 
-        let invariant = initial_amount_a_u128
-            .checked_mul(initial_amount_b_u128)
-            .unwrap();
+        self.swap_pool.update_price_accumulators(
+            Clock::get()?.unix_timestamp,
+            self.swap_pool_token_a_account.amount,
+            self.swap_pool_token_b_account.amount,
+            invariant,
+        )?;
 
-        if is_token_in_token_a {
-            require!(
-                self.user_token_a_account.amount >= amount,
-                SwapverseError::NotEnoughTokens
-            );
-
-            let effective_token_a_amount_u128 = self
-                .swap_pool_token_a_account
-                .amount
-                .checked_add(amount)
-                .unwrap() as u128;
-            let effective_token_b_amount = invariant
-                .checked_div(effective_token_a_amount_u128)
-                .unwrap() as u64;
-            let output_amount_u128 = self
-                .swap_pool_token_b_account
-                .amount
-                .checked_sub(effective_token_b_amount)
-                .unwrap() as u128;
-
-            let treasury_share_u128 = output_amount_u128
-                .checked_mul(self.swap_pool.swap_fee_percentage as u128)
-                .unwrap()
-                .checked_div(100)
-                .unwrap();
-            let user_share_u128 = output_amount_u128.checked_sub(treasury_share_u128).unwrap();
-
-            require!(
-                user_share_u128 as u64 >= min_amount_out,
-                SwapverseError::NotEnoughOutput
-            );
-
-            self.swap_pool.profit_of_token_a_amount_to_be_distributed = self
-                .swap_pool
-                .profit_of_token_a_amount_to_be_distributed
-                .checked_add(treasury_share_u128 as u64)
-                .unwrap();
+        // the referral slice of the fee goes straight to the referrer, the rest is pool profit
+        let referral_amount = referral.as_ref().map_or(0, |(_, amount)| *amount);
+        let treasury_amount = treasury_amount.checked_sub(referral_amount).unwrap();
+        self.swap_pool
+            .record_swap_fee(is_token_in_token_a, treasury_amount);
 
+        if is_token_in_token_a {
             signed_transfer_tokens(
-                user_share_u128 as u64,
+                user_amount_out,
                 &mut self.swap_pool_token_b_account,
                 &mut self.user_token_b_account,
                 &self.signing_authority,
@@ -160,7 +168,7 @@ impl<'info> SwapToken<'info> {
             )?;
 
             signed_transfer_tokens(
-                treasury_share_u128 as u64,
+                treasury_amount,
                 &mut self.swap_pool_token_b_account,
                 &mut self.swap_pool_treasury_token_b_account,
                 &self.signing_authority,
@@ -169,52 +177,15 @@ impl<'info> SwapToken<'info> {
             )?;
 
             transfer_tokens(
-                amount,
+                amount_in,
                 &mut self.user_token_a_account,
                 &mut self.swap_pool_token_a_account,
                 &self.user,
                 &self.token_program,
             )?;
         } else {
-            require!(
-                self.user_token_b_account.amount >= amount,
-                SwapverseError::NotEnoughTokens
-            );
-
-            let effective_token_b_amount_u128 = self
-                .swap_pool_token_b_account
-                .amount
-                .checked_add(amount)
-                .unwrap() as u128;
-            let effective_token_a_amount = invariant
-                .checked_div(effective_token_b_amount_u128)
-                .unwrap() as u64;
-            let output_amount_u128 = self
-                .swap_pool_token_a_account
-                .amount
-                .checked_sub(effective_token_a_amount)
-                .unwrap() as u128;
-
-            let treasury_share_u128 = output_amount_u128
-                .checked_mul(self.swap_pool.swap_fee_percentage as u128)
-                .unwrap()
-                .checked_div(100)
-                .unwrap();
-            let user_share_u128 = output_amount_u128.checked_sub(treasury_share_u128).unwrap();
-
-            require!(
-                user_share_u128 as u64 >= min_amount_out,
-                SwapverseError::NotEnoughOutput
-            );
-
-            self.swap_pool.profit_of_token_b_amount_to_be_distributed = self
-                .swap_pool
-                .profit_of_token_b_amount_to_be_distributed
-                .checked_add(treasury_share_u128 as u64)
-                .unwrap();
-
             signed_transfer_tokens(
-                user_share_u128 as u64,
+                user_amount_out,
                 &mut self.swap_pool_token_a_account,
                 &mut self.user_token_a_account,
                 &self.signing_authority,
@@ -223,7 +194,7 @@ impl<'info> SwapToken<'info> {
             )?;
 
             signed_transfer_tokens(
-                treasury_share_u128 as u64,
+                treasury_amount,
                 &mut self.swap_pool_token_a_account,
                 &mut self.swap_pool_treasury_token_a_account,
                 &self.signing_authority,
@@ -232,7 +203,7 @@ impl<'info> SwapToken<'info> {
             )?;
 
             transfer_tokens(
-                amount,
+                amount_in,
                 &mut self.user_token_b_account,
                 &mut self.swap_pool_token_b_account,
                 &self.user,
@@ -240,6 +211,166 @@ impl<'info> SwapToken<'info> {
             )?;
         }
 
-        Ok(())
+        if let Some((mut referrer_token_account, referral_amount)) = referral {
+            let from = if is_token_in_token_a {
+                &mut self.swap_pool_token_b_account
+            } else {
+                &mut self.swap_pool_token_a_account
+            };
+            signed_transfer_tokens(
+                referral_amount,
+                from,
+                &mut referrer_token_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+        }
+
+        self.swap_pool
+            .reconcile_treasury(true, self.swap_pool_treasury_token_a_account.amount)?;
+        self.swap_pool
+            .reconcile_treasury(false, self.swap_pool_treasury_token_b_account.amount)
+    }
+
+    // referrer token account is read from the remaining accounts, it has to hold the token out
+    fn referral(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        referral_share_bps: u16,
+        treasury_share: u64,
+        is_token_in_token_a: bool,
+    ) -> Result<Option<(Account<'info, TokenAccount>, u64)>> {
+        require!(
+            referral_share_bps <= self.global_state.max_referral_share_bps,
+            SwapverseError::ReferralShareTooHigh
+        );
+        if referral_share_bps == 0 {
+            return Ok(None);
+        }
+
+        let referrer_token_account = Account::<TokenAccount>::try_from(
+            remaining_accounts
+                .first()
+                .ok_or_else(|| error!(SwapverseError::ReferrerAccountMissing))?,
+        )?;
+        let token_out_mint = if is_token_in_token_a {
+            self.swap_pool.token_b_mint
+        } else {
+            self.swap_pool.token_a_mint
+        };
+        require_keys_eq!(
+            referrer_token_account.mint,
+            token_out_mint,
+            SwapverseError::InvalidReferrerTokenAccountMint
+        );
+        // the swapper should not get back a slice of its own fee
+        require_keys_neq!(
+            referrer_token_account.owner,
+            self.user.key(),
+            SwapverseError::SelfReferral
+        );
+
+        let referral_amount = (treasury_share as u128)
+            .checked_mul(referral_share_bps as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64;
+
+        Ok(Some((referrer_token_account, referral_amount)))
+    }
+
+    pub fn swap_token(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount: u64,
+        min_amount_out: u64,
+        is_token_in_token_a: bool,
+        deadline: Option<i64>,
+        referral_share_bps: u16,
+    ) -> Result<()> {
+        self.check_swap_open(deadline)?;
+
+        let (user_token_in_amount, reserve_in, reserve_out) =
+            self.swap_balances(is_token_in_token_a);
+
+        require!(
+            user_token_in_amount >= amount,
+            SwapverseError::NotEnoughTokens
+        );
+
+        let invariant = self
+            .swap_pool
+            .invariant(reserve_in, reserve_out, is_token_in_token_a)?;
+        let (user_share, treasury_share) = self.swap_pool.swap_exact_in(
+            amount,
+            reserve_in,
+            reserve_out,
+            is_token_in_token_a,
+            invariant,
+        )?;
+
+        require!(
+            user_share >= min_amount_out,
+            SwapverseError::NotEnoughOutput
+        );
+
+        let referral = self.referral(
+            remaining_accounts,
+            referral_share_bps,
+            treasury_share,
+            is_token_in_token_a,
+        )?;
+
+        self.execute_swap(
+            amount,
+            user_share,
+            treasury_share,
+            is_token_in_token_a,
+            invariant,
+            referral,
+        )
+    }
+
+    pub fn swap_token_exact_out(
+        &mut self,
+        amount_out: u64,
+        max_amount_in: u64,
+        is_token_in_token_a: bool,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        self.check_swap_open(deadline)?;
+
+        let (user_token_in_amount, reserve_in, reserve_out) =
+            self.swap_balances(is_token_in_token_a);
+
+        let invariant = self
+            .swap_pool
+            .invariant(reserve_in, reserve_out, is_token_in_token_a)?;
+        let (amount_in, treasury_share) = self.swap_pool.swap_exact_out(
+            amount_out,
+            reserve_in,
+            reserve_out,
+            is_token_in_token_a,
+            invariant,
+        )?;
+
+        require!(
+            amount_in <= max_amount_in,
+            SwapverseError::ExcessiveInputAmount
+        );
+        require!(
+            user_token_in_amount >= amount_in,
+            SwapverseError::NotEnoughTokens
+        );
+
+        self.execute_swap(
+            amount_in,
+            amount_out,
+            treasury_share,
+            is_token_in_token_a,
+            invariant,
+            None,
+        )
     }
 }
@@ -0,0 +1,44 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::GlobalState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+        has_one = admin @ SwapverseError::UnauthorizedSigner,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+}
+
+impl<'info> UpdateFeeConfig<'info> {
+    pub fn update_fee_config(
+        &mut self,
+        min_swap_fee_bps: u16,
+        max_swap_fee_bps: u16,
+        min_swapverse_fee_bps: u16,
+        max_swapverse_fee_bps: u16,
+    ) -> Result<()> {
+        require!(
+            min_swap_fee_bps <= max_swap_fee_bps && max_swap_fee_bps as u64 <= BPS_DENOMINATOR,
+            SwapverseError::InvalidFeeBounds
+        );
+        require!(
+            min_swapverse_fee_bps <= max_swapverse_fee_bps
+                && max_swapverse_fee_bps as u64 <= BPS_DENOMINATOR,
+            SwapverseError::InvalidFeeBounds
+        );
+
+        self.global_state.min_swap_fee_bps = min_swap_fee_bps;
+        self.global_state.max_swap_fee_bps = max_swap_fee_bps;
+        self.global_state.min_swapverse_fee_bps = min_swapverse_fee_bps;
+        self.global_state.max_swapverse_fee_bps = max_swapverse_fee_bps;
+
+        Ok(())
+    }
+}
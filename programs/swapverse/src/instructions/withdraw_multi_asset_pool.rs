@@ -0,0 +1,160 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::spl_token_utils::{burn_tokens, signed_transfer_tokens};
+use crate::states::{GlobalState, MultiAssetPool, PoolStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(token_index: u8)]
+pub struct WithdrawMultiAssetPool<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_state: Box<Account<'info, GlobalState>>,
+
+    /// CHECK: we only read from this address
+    #[account(
+        seeds = [SIGNING_AUTHORITY_SEED.as_bytes()],
+        bump
+    )]
+    pub signing_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [multi_asset_pool.pool_number.to_le_bytes().as_ref(), MULTI_ASSET_POOL_SEED.as_bytes()],
+        bump,
+        constraint = multi_asset_pool.is_token_index(token_index) @ SwapverseError::InvalidTokenIndex,
+    )]
+    pub multi_asset_pool: Box<Account<'info, MultiAssetPool>>,
+
+    #[account(
+        mut,
+        constraint = pool_share_token_mint.key() == multi_asset_pool.pool_share_token_mints[token_index as usize] @ SwapverseError::InvalidPoolShareTokenMint
+    )]
+    pub pool_share_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_share_token_mint,
+        associated_token::authority = investor
+    )]
+    pub investor_pool_share_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> WithdrawMultiAssetPool<'info> {
+    // the pool token account and investor token account of every token of the pool are given
+    // in pairs in the remaining accounts, in the order of their token indexes
+    pub fn withdraw_multi_asset_pool(
+        &mut self,
+        token_accounts: &[AccountInfo<'info>],
+        token_index: u8,
+    ) -> Result<()> {
+        // pools which were not settled by a keeper yet are settled on the first withdrawal
+        self.multi_asset_pool.settle(Clock::get()?.unix_timestamp)?;
+
+        require!(
+            self.multi_asset_pool.status.is_open_for_withdrawal(),
+            SwapverseError::SwapPoolNotOpenForWithdrawal
+        );
+
+        let no_of_tokens = self.multi_asset_pool.no_of_tokens as usize;
+        require!(
+            token_accounts.len() == no_of_tokens * 2,
+            SwapverseError::InvalidPoolTokenAccounts
+        );
+
+        let investor_pool_share_amount = self.investor_pool_share_token_account.amount;
+        require!(
+            investor_pool_share_amount > 0,
+            SwapverseError::WithdrawAmountIsZero
+        );
+
+        burn_tokens(
+            investor_pool_share_amount,
+            &mut self.investor_pool_share_token_account,
+            &mut self.pool_share_token_mint,
+            &self.signing_authority,
+            &self.token_program,
+            &self.global_state,
+            &self.investor.to_account_info(),
+        )?;
+        self.multi_asset_pool.pool_shares_outstanding = self
+            .multi_asset_pool
+            .pool_shares_outstanding
+            .checked_sub(investor_pool_share_amount)
+            .unwrap();
+
+        let normalized_pool_share_amount = self
+            .multi_asset_pool
+            .normalize(investor_pool_share_amount, token_index as usize)?;
+        let multi_asset_pool_key = self.multi_asset_pool.key();
+        let total_pool_shares = self.multi_asset_pool.total_pool_shares();
+        for (index, pair) in token_accounts.chunks(2).enumerate() {
+            // a matured pool pays every share out of the whole basket, an unfilled one just
+            // gives the deposit back
+            let withdraw_amount = if self.multi_asset_pool.status == PoolStatus::Matured {
+                (self.multi_asset_pool.amounts_to_be_distributed[index] as u128)
+                    .checked_mul(normalized_pool_share_amount as u128)
+                    .unwrap()
+                    .checked_div(total_pool_shares as u128)
+                    .unwrap() as u64
+            } else if index == token_index as usize {
+                investor_pool_share_amount
+            } else {
+                0
+            };
+            if withdraw_amount == 0 {
+                continue;
+            }
+
+            let token_mint = self.multi_asset_pool.token_mints[index];
+            let (pool_token_address, _) = Pubkey::find_program_address(
+                &[multi_asset_pool_key.as_ref(), token_mint.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                pair[0].key(),
+                pool_token_address,
+                SwapverseError::InvalidPoolTokenAccounts
+            );
+            let mut multi_asset_pool_token_account = Account::<TokenAccount>::try_from(&pair[0])?;
+            let mut investor_token_account = Account::<TokenAccount>::try_from(&pair[1])?;
+            require_keys_eq!(
+                investor_token_account.owner,
+                self.investor.key(),
+                SwapverseError::InvalidInvestorTokenAccountOwner
+            );
+            require_keys_eq!(
+                investor_token_account.mint,
+                token_mint,
+                SwapverseError::InvalidInvestorTokenAccountMint
+            );
+
+            signed_transfer_tokens(
+                withdraw_amount,
+                &mut multi_asset_pool_token_account,
+                &mut investor_token_account,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+            )?;
+        }
+
+        if self.multi_asset_pool.status == PoolStatus::Matured
+            && self.multi_asset_pool.pool_shares_outstanding == 0
+        {
+            self.multi_asset_pool.transition_to(PoolStatus::Closed)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::constants::*;
 use crate::error::SwapverseError;
-use crate::spl_token_utils::signed_transfer_tokens;
-use crate::states::{GlobalState, InvestorPoolInfo, SwapPool};
+use crate::spl_token_utils::{burn_tokens, signed_transfer_tokens};
+use crate::states::{GlobalState, InvestorPoolInfo, PoolStatus, SwapPool};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -29,6 +29,7 @@ pub struct WithdrawSwapPool<'info> {
         mut,
         seeds = [swap_pool.pool_number.to_le_bytes().as_ref(), SWAP_POOL_SEED.as_bytes()],
         bump,
+        constraint = swap_pool.flash_loan_amount == 0 @ SwapverseError::FlashLoanOutstanding,
     )]
     pub swap_pool: Box<Account<'info, SwapPool>>,
 
@@ -116,45 +117,16 @@ pub struct WithdrawSwapPool<'info> {
 }
 
 impl<'info> WithdrawSwapPool<'info> {
-    fn check_for_withdrawal_open(&mut self) {
-        if !self.swap_pool.open_for_withdrawal {
-            let time_now = Clock::get().unwrap().unix_timestamp;
-            if !self.swap_pool.active_for_swap {
-                let max_activation_time = self
-                    .swap_pool
-                    .created_at
-                    .checked_add((self.swap_pool.max_days_to_fill as i64) * 24 * 60 * 60)
-                    .unwrap();
-                if time_now > max_activation_time {
-                    self.swap_pool.open_for_investment = false;
-                    self.swap_pool.open_for_withdrawal = true;
-                    self.set_withdrawable_values();
-                }
-            } else {
-                let max_pool_life = self
-                    .swap_pool
-                    .created_at
-                    .checked_add((self.swap_pool.swap_life_in_days as i64) * 24 * 60 * 60)
-                    .unwrap();
-                if time_now > max_pool_life {
-                    self.swap_pool.open_for_investment = false;
-                    self.swap_pool.open_for_withdrawal = true;
-                    self.set_withdrawable_values();
-                }
-            }
-        }
-    }
-
-    fn set_withdrawable_values(&mut self) {
-        self.swap_pool.token_a_amount_to_be_distributed = self.swap_pool_token_a_account.amount;
-        self.swap_pool.token_b_amount_to_be_distributed = self.swap_pool_token_b_account.amount;
-    }
-
     pub fn withdraw_swap_pool(&mut self, is_token_a: bool) -> Result<()> {
-        self.check_for_withdrawal_open();
+        // pools which were not settled by a keeper yet are settled on the first withdrawal
+        self.swap_pool.settle(
+            Clock::get()?.unix_timestamp,
+            self.swap_pool_token_a_account.amount,
+            self.swap_pool_token_b_account.amount,
+        )?;
 
         require!(
-            self.swap_pool.open_for_withdrawal,
+            self.swap_pool.status.is_open_for_withdrawal(),
             SwapverseError::SwapPoolNotOpenForWithdrawal
         );
 
@@ -163,45 +135,71 @@ impl<'info> WithdrawSwapPool<'info> {
         } else {
             self.investor_pool_share_token_b_account.amount
         };
+        require!(
+            investor_pool_share_amount > 0,
+            SwapverseError::WithdrawAmountIsZero
+        );
 
-        let pool_distribution_token_amount = if is_token_a {
-            self.swap_pool.token_a_amount_to_be_distributed
-        } else {
-            self.swap_pool.token_b_amount_to_be_distributed
-        };
-        let initial_token_amount = if self.swap_pool.activated_at != i64::MAX {
-            if is_token_a {
-                self.swap_pool.initial_amount_a
-            } else {
-                self.swap_pool.initial_amount_b
-            }
-        } else {
-            pool_distribution_token_amount
-        };
-
-        let pool_distribution_token_amount_u128 = pool_distribution_token_amount as u128;
-        let withdraw_amount = pool_distribution_token_amount_u128
-            .checked_mul(investor_pool_share_amount as u128)
-            .unwrap()
-            .checked_div(initial_token_amount as u128)
-            .unwrap() as u64;
+        let (withdraw_amount, other_token_amount) = self
+            .swap_pool
+            .withdrawal_amounts(investor_pool_share_amount, is_token_a)?;
 
         let withdraw_a_amount = if is_token_a {
             withdraw_amount
         } else {
-            investor_pool_share_amount
-                .checked_sub(withdraw_amount)
-                .unwrap()
+            other_token_amount
         };
 
         let withdraw_b_amount = if is_token_a {
-            investor_pool_share_amount
-                .checked_sub(withdraw_amount)
-                .unwrap()
+            other_token_amount
         } else {
             withdraw_amount
         };
 
+        // redeemed shares are burnt so they can not be withdrawn again
+        if is_token_a {
+            burn_tokens(
+                investor_pool_share_amount,
+                &mut self.investor_pool_share_token_a_account,
+                &mut self.pool_share_token_a_mint,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+                &self.investor.to_account_info(),
+            )?;
+            self.investor_pool_info.pool_share_token_a_burned = self
+                .investor_pool_info
+                .pool_share_token_a_burned
+                .checked_add(investor_pool_share_amount)
+                .unwrap();
+        } else {
+            burn_tokens(
+                investor_pool_share_amount,
+                &mut self.investor_pool_share_token_b_account,
+                &mut self.pool_share_token_b_mint,
+                &self.signing_authority,
+                &self.token_program,
+                &self.global_state,
+                &self.investor.to_account_info(),
+            )?;
+            self.investor_pool_info.pool_share_token_b_burned = self
+                .investor_pool_info
+                .pool_share_token_b_burned
+                .checked_add(investor_pool_share_amount)
+                .unwrap();
+        }
+
+        self.investor_pool_info.token_a_withdrawn = self
+            .investor_pool_info
+            .token_a_withdrawn
+            .checked_add(withdraw_a_amount)
+            .unwrap();
+        self.investor_pool_info.token_b_withdrawn = self
+            .investor_pool_info
+            .token_b_withdrawn
+            .checked_add(withdraw_b_amount)
+            .unwrap();
+
         signed_transfer_tokens(
             withdraw_a_amount,
             &mut self.swap_pool_token_a_account,
@@ -220,6 +218,13 @@ impl<'info> WithdrawSwapPool<'info> {
             &self.global_state,
         )?;
 
+        if self.swap_pool.status == PoolStatus::Matured
+            && self.pool_share_token_a_mint.supply == 0
+            && self.pool_share_token_b_mint.supply == 0
+        {
+            self.swap_pool.transition_to(PoolStatus::Closed)?;
+        }
+
         Ok(())
     }
 }
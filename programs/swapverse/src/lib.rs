@@ -1,12 +1,15 @@
 use anchor_lang::prelude::*;
 
 pub mod constants;
+pub mod curves;
 pub mod error;
 pub mod instructions;
+pub mod migration_utils;
 pub mod spl_token_utils;
 pub mod states;
 
 use crate::instructions::*;
+use crate::states::{CurveType, PoolLifeStart};
 
 declare_id!("AeFLgMmKmVjLUv4jBGjXsrNf4MKPaVate5fNmqrDDoin");
 
@@ -27,20 +30,28 @@ pub mod swapverse {
         ctx: Context<CreateSwapPool>,
         initial_amount_a: u64,
         initial_amount_b: u64,
-        swap_fee_percentage: u8,
-        swapverse_fee_percentage: u8,
+        curve_type: CurveType,
+        amp_coefficient: u64,
+        fixed_ratio: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
         min_investment_amount: u64,
         max_days_to_fill: u8,
         swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
     ) -> Result<()> {
         ctx.accounts.create_swap_pool(
             initial_amount_a,
             initial_amount_b,
-            swap_fee_percentage,
-            swapverse_fee_percentage,
+            curve_type,
+            amp_coefficient,
+            fixed_ratio,
+            swap_fee_bps,
+            swapverse_fee_bps,
             min_investment_amount,
             max_days_to_fill,
             swap_life_in_days,
+            life_starts_at,
         )
     }
 
@@ -52,17 +63,252 @@ pub mod swapverse {
         ctx.accounts.withdraw_swap_pool(is_token_a)
     }
 
-    pub fn swap_token(
-        ctx: Context<SwapToken>,
+    pub fn swap_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToken<'info>>,
         amount: u64,
         min_amount_out: u64,
         is_token_in_token_a: bool,
+        deadline: Option<i64>,
+        referral_share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.swap_token(
+            ctx.remaining_accounts,
+            amount,
+            min_amount_out,
+            is_token_in_token_a,
+            deadline,
+            referral_share_bps,
+        )
+    }
+
+    pub fn swap_token_exact_out(
+        ctx: Context<SwapToken>,
+        amount_out: u64,
+        max_amount_in: u64,
+        is_token_in_token_a: bool,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.swap_token_exact_out(
+            amount_out,
+            max_amount_in,
+            is_token_in_token_a,
+            deadline,
+        )
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapRoute<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
         ctx.accounts
-            .swap_token(amount, min_amount_out, is_token_in_token_a)
+            .swap_route(ctx.remaining_accounts, amount, min_amount_out, deadline)
+    }
+
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        amount: u64,
+        is_token_in_token_a: bool,
+    ) -> Result<SwapQuote> {
+        ctx.accounts.quote_swap(amount, is_token_in_token_a)
+    }
+
+    pub fn flash_loan(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        ctx.accounts.flash_loan(amount)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        ctx.accounts.flash_repay()
     }
 
     pub fn claim_profit(ctx: Context<ClaimProfit>) -> Result<()> {
         ctx.accounts.claim_profit()
     }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_admin(new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept_admin()
+    }
+
+    pub fn register_token_mint(ctx: Context<RegisterTokenMint>) -> Result<()> {
+        ctx.accounts.register_token_mint()
+    }
+
+    pub fn deregister_token_mint(ctx: Context<DeregisterTokenMint>) -> Result<()> {
+        ctx.accounts.deregister_token_mint()
+    }
+
+    pub fn set_protocol_fee_wallet(
+        ctx: Context<SetProtocolFeeWallet>,
+        protocol_fee_wallet: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.set_protocol_fee_wallet(protocol_fee_wallet)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        ctx.accounts.collect_protocol_fees()
+    }
+
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        min_swap_fee_bps: u16,
+        max_swap_fee_bps: u16,
+        min_swapverse_fee_bps: u16,
+        max_swapverse_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.update_fee_config(
+            min_swap_fee_bps,
+            max_swap_fee_bps,
+            min_swapverse_fee_bps,
+            max_swapverse_fee_bps,
+        )
+    }
+
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+        ctx.accounts.set_global_pause(paused)
+    }
+
+    pub fn set_pool_pause(ctx: Context<SetPoolPause>, paused: bool) -> Result<()> {
+        ctx.accounts.set_pool_pause(paused)
+    }
+
+    pub fn set_pool_depeg_guard(
+        ctx: Context<SetPoolDepegGuard>,
+        max_price_deviation_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_pool_depeg_guard(max_price_deviation_bps)
+    }
+
+    pub fn set_dynamic_fee(
+        ctx: Context<SetDynamicFee>,
+        dynamic_fee_enabled: bool,
+        min_dynamic_fee_bps: u16,
+        max_dynamic_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_dynamic_fee(
+            dynamic_fee_enabled,
+            min_dynamic_fee_bps,
+            max_dynamic_fee_bps,
+        )
+    }
+
+    pub fn set_max_referral_share(
+        ctx: Context<SetMaxReferralShare>,
+        max_referral_share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_max_referral_share(max_referral_share_bps)
+    }
+
+    pub fn cancel_swap_pool(ctx: Context<CancelSwapPool>) -> Result<()> {
+        ctx.accounts.cancel_swap_pool()
+    }
+
+    pub fn settle_pool(ctx: Context<SettlePool>) -> Result<()> {
+        ctx.accounts.settle_pool()
+    }
+
+    pub fn migrate_global_state(ctx: Context<MigrateGlobalState>) -> Result<()> {
+        ctx.accounts.migrate_global_state()
+    }
+
+    pub fn migrate_swap_pool(ctx: Context<MigrateSwapPool>) -> Result<()> {
+        ctx.accounts.migrate_swap_pool()
+    }
+
+    pub fn migrate_investor_pool_info(ctx: Context<MigrateInvestorPoolInfo>) -> Result<()> {
+        ctx.accounts.migrate_investor_pool_info()
+    }
+
+    pub fn create_multi_asset_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMultiAssetPool<'info>>,
+        initial_amount: u64,
+        amp_coefficient: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
+        min_investment_amount: u64,
+        max_days_to_fill: u8,
+        swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
+    ) -> Result<()> {
+        ctx.accounts.create_multi_asset_pool(
+            ctx.remaining_accounts,
+            initial_amount,
+            amp_coefficient,
+            swap_fee_bps,
+            swapverse_fee_bps,
+            min_investment_amount,
+            max_days_to_fill,
+            swap_life_in_days,
+            life_starts_at,
+        )
+    }
+
+    pub fn initialize_multi_asset_pool_token(
+        ctx: Context<InitializeMultiAssetPoolToken>,
+        _token_index: u8,
+    ) -> Result<()> {
+        ctx.accounts.initialize_multi_asset_pool_token()
+    }
+
+    pub fn invest_multi_asset_pool(
+        ctx: Context<InvestMultiAssetPool>,
+        token_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.invest_multi_asset_pool(token_index, amount)
+    }
+
+    pub fn swap_multi_asset_pool(
+        ctx: Context<SwapMultiAssetPool>,
+        token_in_index: u8,
+        token_out_index: u8,
+        amount: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.swap_multi_asset_pool(
+            token_in_index,
+            token_out_index,
+            amount,
+            min_amount_out,
+            deadline,
+        )
+    }
+
+    pub fn settle_multi_asset_pool(ctx: Context<SettleMultiAssetPool>) -> Result<()> {
+        ctx.accounts.settle_multi_asset_pool()
+    }
+
+    pub fn withdraw_multi_asset_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawMultiAssetPool<'info>>,
+        token_index: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_multi_asset_pool(ctx.remaining_accounts, token_index)
+    }
+
+    pub fn claim_multi_asset_profit(
+        ctx: Context<ClaimMultiAssetProfit>,
+        token_index: u8,
+    ) -> Result<()> {
+        ctx.accounts.claim_multi_asset_profit(token_index)
+    }
+
+    pub fn collect_multi_asset_protocol_fee(
+        ctx: Context<CollectMultiAssetProtocolFee>,
+        token_index: u8,
+    ) -> Result<()> {
+        ctx.accounts.collect_multi_asset_protocol_fee(token_index)
+    }
+
+    pub fn set_multi_asset_pool_pause(
+        ctx: Context<SetMultiAssetPoolPause>,
+        paused: bool,
+    ) -> Result<()> {
+        ctx.accounts.set_multi_asset_pool_pause(paused)
+    }
 }
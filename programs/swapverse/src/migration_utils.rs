@@ -0,0 +1,47 @@
+use crate::error::SwapverseError;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+// reads an account of type T still in its legacy layout, accounts which have already been
+// migrated are rejected
+pub fn read_legacy_account<T: Discriminator, L: AnchorDeserialize>(
+    account: &AccountInfo,
+    legacy_space: usize,
+) -> Result<L> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() == legacy_space && data[..8] == T::DISCRIMINATOR,
+        SwapverseError::AccountNotInLegacyLayout
+    );
+    L::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+// resizes an account to its new layout and writes the migrated state, the payer covers the
+// extra rent
+pub fn write_migrated_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    migrated: &T,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent_shortfall > 0 {
+        let transfer_instruction = Transfer {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        transfer(
+            CpiContext::new(system_program.to_account_info(), transfer_instruction),
+            rent_shortfall,
+        )?;
+    }
+
+    account.realloc(space, false)?;
+    let mut data = account.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])
+}
@@ -1,18 +1,43 @@
+use crate::constants::*;
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(Default)]
 pub struct GlobalState{
-    pub token_mints: [Pubkey; 5],
+    // authority allowed to run admin instructions
+    pub admin: Pubkey,
+    // admin proposed by the current admin, has to accept to take over
+    pub pending_admin: Pubkey,
+    // owner of the token accounts receiving the swapverse share of pool profits
+    pub protocol_fee_wallet: Pubkey,
+    // bounds for the fees a pool creator can choose, set by the admin
+    pub min_swap_fee_bps: u16,
+    pub max_swap_fee_bps: u16,
+    pub min_swapverse_fee_bps: u16,
+    pub max_swapverse_fee_bps: u16,
+    // largest part of a swap fee which a swap may send to its referrer
+    pub max_referral_share_bps: u16,
+    // stops swaps and investments in every pool
+    pub paused: bool,
     pub signing_authority_bump: u8,
     pub no_of_swap_pools: u64,
+    pub no_of_multi_asset_pools: u64,
 }
 
 impl GlobalState {
-    pub fn initialize(&mut self, token_mints: [Pubkey; 5], signing_authority_bump: &u8) -> Result<()> {
-        self.token_mints = token_mints;
+    pub fn initialize(&mut self, admin: Pubkey, signing_authority_bump: &u8) -> Result<()> {
+        self.admin = admin;
+        self.pending_admin = Pubkey::default();
+        self.protocol_fee_wallet = admin;
+        self.min_swap_fee_bps = DEFAULT_MIN_SWAP_FEE_BPS;
+        self.max_swap_fee_bps = DEFAULT_MAX_SWAP_FEE_BPS;
+        self.min_swapverse_fee_bps = DEFAULT_MIN_SWAPVERSE_FEE_BPS;
+        self.max_swapverse_fee_bps = DEFAULT_MAX_SWAPVERSE_FEE_BPS;
+        self.max_referral_share_bps = DEFAULT_MAX_REFERRAL_SHARE_BPS;
+        self.paused = false;
         self.signing_authority_bump = *signing_authority_bump;
         self.no_of_swap_pools = 0;
+        self.no_of_multi_asset_pools = 0;
 
         Ok(())
     }
@@ -9,4 +9,7 @@ pub struct InvestorPoolInfo {
     pub token_b_withdrawn: u64,
     pub profit_for_token_a_withdrawn: u64,
     pub profit_for_token_b_withdrawn: u64,
+    // pool share tokens burnt on withdrawal, still count for profit claims
+    pub pool_share_token_a_burned: u64,
+    pub pool_share_token_b_burned: u64,
 }
@@ -0,0 +1,190 @@
+use crate::constants::*;
+use crate::error::SwapverseError;
+use crate::states::{
+    days_after, CurveType, GlobalState, InvestorPoolInfo, PoolLifeStart, PoolStatus, SwapPool,
+};
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+// layouts of the accounts deployed before the pool status, basis point fees and burned pool
+// shares, only read by the migrations which move them to the current layouts
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyGlobalState {
+    pub token_mints: [Pubkey; 5],
+    pub signing_authority_bump: u8,
+    pub no_of_swap_pools: u64,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacySwapPool {
+    pub pool_number: u64,
+    pub active_for_swap: bool,
+    pub open_for_investment: bool,
+    pub open_for_withdrawal: bool,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub pool_share_token_a_mint: Pubkey,
+    pub pool_share_token_b_mint: Pubkey,
+    pub initial_amount_a: u64,
+    pub initial_amount_b: u64,
+    pub swap_fee_percentage: u8,
+    pub swapverse_fee_percentage: u8,
+    pub min_investment_amount: u64,
+    pub max_days_to_fill: u8,
+    pub swap_life_in_days: u64,
+    pub created_at: i64,
+    // i64::MAX until the pool is activated
+    pub activated_at: i64,
+    pub token_a_amount_to_be_distributed: u64,
+    pub token_b_amount_to_be_distributed: u64,
+    pub profit_of_token_a_amount_to_be_distributed: u64,
+    pub profit_of_token_b_amount_to_be_distributed: u64,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyInvestorPoolInfo {
+    pub investor: Pubkey,
+    pub swap_pool: Pubkey,
+    pub token_a_withdrawn: u64,
+    pub token_b_withdrawn: u64,
+    pub profit_for_token_a_withdrawn: u64,
+    pub profit_for_token_b_withdrawn: u64,
+}
+
+// legacy accounts are told apart from migrated ones by their size
+const _: () = assert!(LegacyGlobalState::SPACE != size_of::<GlobalState>() + 8);
+const _: () = assert!(LegacySwapPool::SPACE != size_of::<SwapPool>() + 8);
+const _: () = assert!(LegacyInvestorPoolInfo::SPACE != size_of::<InvestorPoolInfo>() + 8);
+
+fn percentage_to_bps(percentage: u8) -> u16 {
+    percentage as u16 * 100
+}
+
+impl LegacyGlobalState {
+    pub const SPACE: usize = size_of::<LegacyGlobalState>() + 8;
+
+    // the whitelisted token_mints are dropped, they have to be registered again with
+    // register_token_mint before new pools can use them
+    pub fn migrate(self, admin: Pubkey) -> GlobalState {
+        GlobalState {
+            admin,
+            pending_admin: Pubkey::default(),
+            protocol_fee_wallet: admin,
+            min_swap_fee_bps: DEFAULT_MIN_SWAP_FEE_BPS,
+            max_swap_fee_bps: DEFAULT_MAX_SWAP_FEE_BPS,
+            min_swapverse_fee_bps: DEFAULT_MIN_SWAPVERSE_FEE_BPS,
+            max_swapverse_fee_bps: DEFAULT_MAX_SWAPVERSE_FEE_BPS,
+            max_referral_share_bps: DEFAULT_MAX_REFERRAL_SHARE_BPS,
+            paused: false,
+            signing_authority_bump: self.signing_authority_bump,
+            no_of_swap_pools: self.no_of_swap_pools,
+            no_of_multi_asset_pools: 0,
+        }
+    }
+}
+
+impl LegacySwapPool {
+    pub const SPACE: usize = size_of::<LegacySwapPool>() + 8;
+
+    fn is_activated(&self) -> bool {
+        self.activated_at != i64::MAX
+    }
+
+    // legacy withdrawals did not burn pool shares, so the shares already paid out of a pool
+    // open for withdrawal cannot be told apart and such pools are not migrated
+    fn status(&self) -> Result<PoolStatus> {
+        require!(
+            !self.open_for_withdrawal,
+            SwapverseError::LegacySwapPoolOpenForWithdrawal
+        );
+
+        if self.active_for_swap {
+            Ok(PoolStatus::Active)
+        } else if self.open_for_investment {
+            Ok(PoolStatus::Funding)
+        } else {
+            err!(SwapverseError::AccountNotInLegacyLayout)
+        }
+    }
+
+    // legacy pools were constant product pools whose life counted from creation. their swaps
+    // credited each fee to the profit of the other token, so the profits are swapped back to
+    // the token they were charged in and what already left a treasury is counted as claimed
+    pub fn migrate(
+        self,
+        treasury_token_a_balance: u64,
+        treasury_token_b_balance: u64,
+    ) -> Result<SwapPool> {
+        Ok(SwapPool {
+            pool_number: self.pool_number,
+            status: self.status()?,
+            paused: false,
+            token_a_mint: self.token_a_mint,
+            token_b_mint: self.token_b_mint,
+            pool_share_token_a_mint: self.pool_share_token_a_mint,
+            pool_share_token_b_mint: self.pool_share_token_b_mint,
+            initial_amount_a: self.initial_amount_a,
+            initial_amount_b: self.initial_amount_b,
+            curve_type: CurveType::ConstantProduct,
+            amp_coefficient: 0,
+            fixed_ratio: 0,
+            swap_fee_bps: percentage_to_bps(self.swap_fee_percentage),
+            swapverse_fee_bps: percentage_to_bps(self.swapverse_fee_percentage),
+            min_investment_amount: self.min_investment_amount,
+            max_days_to_fill: self.max_days_to_fill,
+            swap_life_in_days: self.swap_life_in_days,
+            life_starts_at: PoolLifeStart::CreatedAt,
+            created_at: self.created_at,
+            activated_at: if self.is_activated() {
+                self.activated_at
+            } else {
+                0
+            },
+            matures_at: days_after(self.created_at, self.swap_life_in_days)?,
+            token_a_amount_to_be_distributed: self.token_a_amount_to_be_distributed,
+            token_b_amount_to_be_distributed: self.token_b_amount_to_be_distributed,
+            profit_of_token_a_amount_to_be_distributed: self
+                .profit_of_token_b_amount_to_be_distributed,
+            profit_of_token_b_amount_to_be_distributed: self
+                .profit_of_token_a_amount_to_be_distributed,
+            profit_of_token_a_claimed: self
+                .profit_of_token_b_amount_to_be_distributed
+                .saturating_sub(treasury_token_a_balance),
+            profit_of_token_b_claimed: self
+                .profit_of_token_a_amount_to_be_distributed
+                .saturating_sub(treasury_token_b_balance),
+            protocol_fee_of_token_a_collected: 0,
+            protocol_fee_of_token_b_collected: 0,
+            flash_loan_amount: 0,
+            flash_loan_is_token_a: false,
+            price_a_cumulative: 0,
+            price_b_cumulative: 0,
+            last_price_update_at: 0,
+            max_price_deviation_bps: 0,
+            dynamic_fee_enabled: false,
+            min_dynamic_fee_bps: 0,
+            max_dynamic_fee_bps: 0,
+            token_a_decimals: 0,
+            token_b_decimals: 0,
+        })
+    }
+}
+
+impl LegacyInvestorPoolInfo {
+    pub const SPACE: usize = size_of::<LegacyInvestorPoolInfo>() + 8;
+
+    // legacy withdrawals did not burn pool shares
+    pub fn migrate(self) -> InvestorPoolInfo {
+        InvestorPoolInfo {
+            investor: self.investor,
+            swap_pool: self.swap_pool,
+            token_a_withdrawn: self.token_a_withdrawn,
+            token_b_withdrawn: self.token_b_withdrawn,
+            profit_for_token_a_withdrawn: self.profit_for_token_a_withdrawn,
+            profit_for_token_b_withdrawn: self.profit_for_token_b_withdrawn,
+            pool_share_token_a_burned: 0,
+            pool_share_token_b_burned: 0,
+        }
+    }
+}
@@ -1,7 +1,15 @@
 mod global_state;
 mod swap_pool;
 mod investor_pool_info;
+mod token_mint_registry;
+mod legacy;
+mod multi_asset_pool;
+mod multi_asset_investor_info;
 
 pub use global_state::*;
 pub use swap_pool::*;
-pub use investor_pool_info::*;
\ No newline at end of file
+pub use investor_pool_info::*;
+pub use token_mint_registry::*;
+pub use legacy::*;
+pub use multi_asset_pool::*;
+pub use multi_asset_investor_info::*;
\ No newline at end of file
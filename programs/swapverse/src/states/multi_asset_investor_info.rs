@@ -0,0 +1,23 @@
+use crate::constants::MAX_MULTI_ASSET_POOL_TOKENS;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default)]
+pub struct MultiAssetInvestorInfo {
+    pub investor: Pubkey,
+    pub multi_asset_pool: Pubkey,
+    // pool share tokens minted for the deposits of each token, burning them on withdrawal does
+    // not reduce these so they still count for profit claims
+    pub pool_shares: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    // profit of each token already claimed
+    pub profits_claimed: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+}
+
+impl MultiAssetInvestorInfo {
+    // in units of each token, MultiAssetPool::normalized_pool_shares weighs them by decimals
+    pub fn total_pool_shares(&self) -> u64 {
+        self.pool_shares
+            .iter()
+            .fold(0u64, |total, shares| total.checked_add(*shares).unwrap())
+    }
+}
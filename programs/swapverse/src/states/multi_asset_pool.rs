@@ -0,0 +1,321 @@
+use crate::constants::{BPS_DENOMINATOR, MAX_MULTI_ASSET_POOL_TOKENS};
+use crate::curves::checked;
+use crate::curves::stable_swap::{compute_d, compute_y};
+use crate::error::SwapverseError;
+use crate::states::{days_after, PoolLifeStart, PoolStatus};
+use anchor_lang::prelude::*;
+
+// stable swap pool of MIN_MULTI_ASSET_POOL_TOKENS to MAX_MULTI_ASSET_POOL_TOKENS registered
+// stable coins, the per token arrays hold MAX_MULTI_ASSET_POOL_TOKENS entries of which only
+// the first no_of_tokens are used
+#[account]
+#[derive(Default)]
+pub struct MultiAssetPool {
+    pub pool_number: u64,
+    pub status: PoolStatus,
+    // stops swaps and investments in this pool, set by the admin
+    pub paused: bool,
+    pub no_of_tokens: u8,
+    pub token_mints: [Pubkey; MAX_MULTI_ASSET_POOL_TOKENS],
+    // amounts of every token are priced at the precision of the one with the most decimals
+    pub token_decimals: [u8; MAX_MULTI_ASSET_POOL_TOKENS],
+    // tokens to be given to investors for their deposits of each token
+    pub pool_share_token_mints: [Pubkey; MAX_MULTI_ASSET_POOL_TOKENS],
+    // amount of every token needed to fill the pool, normalized
+    pub initial_amount: u64,
+    // amplification coefficient of the stable swap curve
+    pub amp_coefficient: u64,
+    // basis points of swapped amount to be levied as fee
+    pub swap_fee_bps: u16,
+    // basis points of the swap fee profits going to swapverse treasury
+    pub swapverse_fee_bps: u16,
+    // normalized
+    pub min_investment_amount: u64,
+    // maximum days from creation time to fill the pool
+    pub max_days_to_fill: u8,
+    pub swap_life_in_days: u64,
+    pub life_starts_at: PoolLifeStart,
+    pub created_at: i64,
+    // 0 until the pool is activated
+    pub activated_at: i64,
+    // swaps are accepted till this time, 0 until activation if life starts at activation
+    pub matures_at: i64,
+    // pool share tokens minted and not burnt yet, over every token
+    pub pool_shares_outstanding: u64,
+    // deposits and swaps in minus swaps out of each token, used for pricing
+    pub reserves: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    // used for withdrawals
+    pub amounts_to_be_distributed: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    pub profits_to_be_distributed: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    // investor share of the profits already claimed
+    pub profits_claimed: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    // swapverse share of the profits already sent to the protocol fee wallet
+    pub protocol_fees_collected: [u64; MAX_MULTI_ASSET_POOL_TOKENS],
+}
+
+impl MultiAssetPool {
+    pub fn initialize(
+        &mut self,
+        pool_number: u64,
+        token_mints: &[Pubkey],
+        token_decimals: &[u8],
+        pool_share_token_mints: &[Pubkey],
+        initial_amount: u64,
+        amp_coefficient: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
+        min_investment_amount: u64,
+        max_days_to_fill: u8,
+        swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
+    ) -> Result<()> {
+        self.pool_number = pool_number;
+        self.status = PoolStatus::Funding;
+        self.paused = false;
+
+        self.no_of_tokens = token_mints.len() as u8;
+        self.token_mints = [Pubkey::default(); MAX_MULTI_ASSET_POOL_TOKENS];
+        self.token_mints[..token_mints.len()].copy_from_slice(token_mints);
+        self.token_decimals = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+        self.token_decimals[..token_decimals.len()].copy_from_slice(token_decimals);
+        self.pool_share_token_mints = [Pubkey::default(); MAX_MULTI_ASSET_POOL_TOKENS];
+        self.pool_share_token_mints[..pool_share_token_mints.len()]
+            .copy_from_slice(pool_share_token_mints);
+
+        self.initial_amount = initial_amount;
+        self.amp_coefficient = amp_coefficient;
+        self.swap_fee_bps = swap_fee_bps;
+        self.swapverse_fee_bps = swapverse_fee_bps;
+
+        self.min_investment_amount = min_investment_amount;
+        self.max_days_to_fill = max_days_to_fill;
+        self.swap_life_in_days = swap_life_in_days;
+        self.life_starts_at = life_starts_at;
+
+        self.created_at = Clock::get()?.unix_timestamp;
+        self.activated_at = 0;
+        // pools are activated by their fill deadline at the latest, a life which would not fit
+        // the timestamps from there is refused here rather than at activation
+        self.matures_at = match life_starts_at {
+            PoolLifeStart::CreatedAt => self.life_ends_at(self.created_at)?,
+            PoolLifeStart::ActivatedAt => {
+                self.life_ends_at(self.fill_deadline()?)?;
+                0
+            }
+        };
+
+        self.pool_shares_outstanding = 0;
+        self.reserves = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+        self.amounts_to_be_distributed = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+        self.profits_to_be_distributed = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+        self.profits_claimed = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+        self.protocol_fees_collected = [0; MAX_MULTI_ASSET_POOL_TOKENS];
+
+        Ok(())
+    }
+
+    pub fn is_token_index(&self, token_index: u8) -> bool {
+        token_index < self.no_of_tokens
+    }
+
+    // factor bringing amounts of a token to the precision of the token with the most decimals
+    fn decimals_scale(&self, token_index: usize) -> Result<u64> {
+        let common_decimals = self.token_decimals[..self.no_of_tokens as usize]
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default();
+
+        checked(10u64.checked_pow((common_decimals - self.token_decimals[token_index]) as u32))
+    }
+
+    pub fn normalize(&self, amount: u64, token_index: usize) -> Result<u64> {
+        checked(amount.checked_mul(self.decimals_scale(token_index)?))
+    }
+
+    // amount of a token needed to fill the pool, in units of the token
+    pub fn initial_amount_of(&self, token_index: usize) -> Result<u64> {
+        let scale = self.decimals_scale(token_index)?;
+        let initial_amount = self.initial_amount / scale;
+        require!(
+            initial_amount * scale == self.initial_amount,
+            SwapverseError::InitialAmountsNotSame
+        );
+
+        Ok(initial_amount)
+    }
+
+    // pool shares of every token together, normalized, each of them is worth the same once
+    // the pool is filled
+    pub fn total_pool_shares(&self) -> u64 {
+        self.initial_amount
+            .checked_mul(self.no_of_tokens as u64)
+            .unwrap()
+    }
+
+    // normalized sum of pool shares of each token, such as the deposits of an investor
+    pub fn normalized_pool_shares(
+        &self,
+        pool_shares: &[u64; MAX_MULTI_ASSET_POOL_TOKENS],
+    ) -> Result<u64> {
+        (0..self.no_of_tokens as usize).try_fold(0u64, |total, token_index| {
+            checked(total.checked_add(self.normalize(pool_shares[token_index], token_index)?))
+        })
+    }
+
+    pub fn is_filled(&self) -> Result<bool> {
+        for token_index in 0..self.no_of_tokens as usize {
+            if self.reserves[token_index] != self.initial_amount_of(token_index)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // the only place where the status of a pool is changed
+    pub fn transition_to(&mut self, next_status: PoolStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next_status),
+            SwapverseError::InvalidPoolStatusTransition
+        );
+
+        if next_status == PoolStatus::Active {
+            self.activated_at = Clock::get()?.unix_timestamp;
+            if self.life_starts_at == PoolLifeStart::ActivatedAt {
+                self.matures_at = self.life_ends_at(self.activated_at)?;
+            }
+        }
+        self.status = next_status;
+
+        Ok(())
+    }
+
+    // investments are accepted till this time
+    pub fn fill_deadline(&self) -> Result<i64> {
+        days_after(self.created_at, self.max_days_to_fill as u64)
+    }
+
+    fn life_ends_at(&self, life_started_at: i64) -> Result<i64> {
+        days_after(life_started_at, self.swap_life_in_days)
+    }
+
+    // moves a pool past its fill deadline or maturity to withdrawals and snapshots the
+    // reserves to be distributed, returns false if the pool was not due
+    pub fn settle(&mut self, time_now: i64) -> Result<bool> {
+        let next_status = match self.status {
+            PoolStatus::Funding if time_now > self.fill_deadline()? => PoolStatus::ExpiredUnfilled,
+            PoolStatus::Active if time_now > self.matures_at => PoolStatus::Matured,
+            _ => return Ok(false),
+        };
+
+        self.transition_to(next_status)?;
+        self.amounts_to_be_distributed = self.reserves;
+
+        Ok(true)
+    }
+
+    pub fn check_swap_open(&self, time_now: i64, deadline: Option<i64>) -> Result<()> {
+        require!(time_now <= self.matures_at, SwapverseError::SwapPoolMatured);
+        if let Some(deadline) = deadline {
+            require!(time_now <= deadline, SwapverseError::TransactionExpired);
+        }
+
+        Ok(())
+    }
+
+    // user and treasury shares of the output of an exact input swap, priced on the stable
+    // swap invariant of all the normalized reserves, one normalized unit of token out is kept
+    // by the pool to cover the rounding of the newton iterations
+    pub fn swap_exact_in(
+        &self,
+        token_in_index: usize,
+        token_out_index: usize,
+        amount_in: u64,
+    ) -> Result<(u64, u64)> {
+        let reserves = (0..self.no_of_tokens as usize)
+            .map(|token_index| Ok(self.normalize(self.reserves[token_index], token_index)? as u128))
+            .collect::<Result<Vec<u128>>>()?;
+
+        let d = compute_d(self.amp_coefficient, &reserves)?;
+        let new_reserve_in = checked(
+            reserves[token_in_index]
+                .checked_add(self.normalize(amount_in, token_in_index)? as u128),
+        )?;
+        let new_reserve_out = compute_y(
+            self.amp_coefficient,
+            &reserves,
+            token_in_index,
+            token_out_index,
+            new_reserve_in,
+            d,
+        )?;
+        let output_amount = (reserves[token_out_index]
+            .saturating_sub(new_reserve_out)
+            .saturating_sub(1) as u64)
+            / self.decimals_scale(token_out_index)?;
+
+        let treasury_share = (output_amount as u128)
+            .checked_mul(self.swap_fee_bps as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64;
+        let user_share = output_amount.checked_sub(treasury_share).unwrap();
+
+        Ok((user_share, treasury_share))
+    }
+
+    // the fee is levied on the token going out and sent to its treasury, so it leaves the
+    // reserves and is profit of that token
+    pub fn record_swap(
+        &mut self,
+        token_in_index: usize,
+        token_out_index: usize,
+        amount_in: u64,
+        user_amount_out: u64,
+        treasury_amount: u64,
+    ) {
+        self.reserves[token_in_index] = self.reserves[token_in_index]
+            .checked_add(amount_in)
+            .unwrap();
+        self.reserves[token_out_index] = self.reserves[token_out_index]
+            .checked_sub(user_amount_out.checked_add(treasury_amount).unwrap())
+            .unwrap();
+        self.profits_to_be_distributed[token_out_index] = self.profits_to_be_distributed
+            [token_out_index]
+            .checked_add(treasury_amount)
+            .unwrap();
+    }
+
+    // part of the recorded profit of a token which belongs to swapverse
+    pub fn protocol_share_of_profit(&self, token_index: usize) -> u64 {
+        (self.profits_to_be_distributed[token_index] as u128)
+            .checked_mul(self.swapverse_fee_bps as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64
+    }
+
+    // protocol share of a token which is still lying in the pool treasury
+    pub fn uncollected_protocol_fee(&self, token_index: usize) -> u64 {
+        self.protocol_share_of_profit(token_index)
+            .checked_sub(self.protocol_fees_collected[token_index])
+            .unwrap()
+    }
+
+    // profit of a token which has not been paid out to investors or swapverse yet, should
+    // always be held by the treasury of that token
+    pub fn reconcile_treasury(&self, token_index: usize, treasury_balance: u64) -> Result<()> {
+        let unpaid_profit = self.profits_to_be_distributed[token_index]
+            .checked_sub(self.profits_claimed[token_index])
+            .unwrap()
+            .checked_sub(self.protocol_fees_collected[token_index])
+            .unwrap();
+        require!(
+            unpaid_profit <= treasury_balance,
+            SwapverseError::TreasuryBalanceMismatch
+        );
+
+        Ok(())
+    }
+}
@@ -1,12 +1,95 @@
+use crate::constants::{
+    BPS_DENOMINATOR, FIXED_RATIO_PRECISION, FLASH_LOAN_FEE_BPS, PRICE_SCALE, SECONDS_IN_A_DAY,
+};
+use crate::curves::stable_swap::compute_d;
+use crate::curves::{checked, ConstantProduct, ConstantSum, FixedRatio, StableSwap, SwapCurve};
+use crate::error::SwapverseError;
 use anchor_lang::prelude::*;
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PoolStatus {
+    // accepting investments until both tokens reach their initial amounts
+    #[default]
+    Funding,
+    // filled, open for swaps
+    Active,
+    // life of the pool is over, open for withdrawals
+    Matured,
+    // not filled within max_days_to_fill, investors take their deposits back
+    ExpiredUnfilled,
+    // cancelled by the admin while funding, investors take their deposits back
+    Cancelled,
+    // every investor has withdrawn from the matured pool
+    Closed,
+}
+
+impl PoolStatus {
+    pub fn can_transition_to(&self, next_status: PoolStatus) -> bool {
+        matches!(
+            (self, next_status),
+            (PoolStatus::Funding, PoolStatus::Active)
+                | (PoolStatus::Funding, PoolStatus::ExpiredUnfilled)
+                | (PoolStatus::Funding, PoolStatus::Cancelled)
+                | (PoolStatus::Active, PoolStatus::Matured)
+                | (PoolStatus::Matured, PoolStatus::Closed)
+        )
+    }
+
+    pub fn is_open_for_withdrawal(&self) -> bool {
+        matches!(
+            self,
+            PoolStatus::Matured | PoolStatus::ExpiredUnfilled | PoolStatus::Cancelled
+        )
+    }
+
+    // pools which were active at some point may have profit to claim
+    pub fn has_been_activated(&self) -> bool {
+        matches!(
+            self,
+            PoolStatus::Active | PoolStatus::Matured | PoolStatus::Closed
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PoolLifeStart {
+    // life of the pool counts from its creation, time spent funding is lost
+    #[default]
+    CreatedAt,
+    // life of the pool counts from its activation, investors get the full trading term
+    ActivatedAt,
+}
+
+// time the given number of days after a timestamp, an error if it is out of range
+pub fn days_after(timestamp: i64, days: u64) -> Result<i64> {
+    checked(
+        i64::try_from(days)
+            .ok()
+            .and_then(|days| days.checked_mul(SECONDS_IN_A_DAY))
+            .and_then(|seconds| timestamp.checked_add(seconds)),
+    )
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CurveType {
+    // x * y = k
+    #[default]
+    ConstantProduct,
+    // curve style invariant, flat around the 1:1 peg
+    StableSwap,
+    // x + y = k, 1:1 while reserves last
+    ConstantSum,
+    // fixed rate set at creation, while reserves last
+    FixedRatio,
+}
+
 #[account]
 #[derive(Default)]
 pub struct SwapPool {
     pub pool_number: u64,
-    pub active_for_swap: bool,
-    pub open_for_investment: bool,
-    pub open_for_withdrawal: bool,
+    pub status: PoolStatus,
+    // stops swaps and investments in this pool, set by the admin
+    pub paused: bool,
     pub token_a_mint: Pubkey,
     pub token_b_mint: Pubkey,
     // tokens to be given to investors for their deposits
@@ -16,23 +99,62 @@ pub struct SwapPool {
     pub initial_amount_a: u64,
     // initial amount of token b
     pub initial_amount_b: u64,
-    // percentage of swapped amount for to be levied as fee
-    pub swap_fee_percentage: u8,
-    // percentage of withdrawal_fee_percentage going to swapverse treasury
-    pub swapverse_fee_percentage: u8,
+    // pricing curve of the pool
+    pub curve_type: CurveType,
+    // amplification coefficient of the stable swap curve, unused for other curves
+    pub amp_coefficient: u64,
+    // token b given for FIXED_RATIO_PRECISION units of token a by the fixed ratio curve, both
+    // in normalized units, so a 1:1 rate is FIXED_RATIO_PRECISION whatever the decimals of the
+    // mints are
+    pub fixed_ratio: u64,
+    // basis points of swapped amount to be levied as fee
+    pub swap_fee_bps: u16,
+    // basis points of the swap fee profits going to swapverse treasury
+    pub swapverse_fee_bps: u16,
     // minimum investment amount
     pub min_investment_amount: u64,
     // maximum days from creation time to fill the pool
     pub max_days_to_fill: u8,
     // life of swap in days
     pub swap_life_in_days: u64,
+    // whether the life of swap counts from created_at or activated_at
+    pub life_starts_at: PoolLifeStart,
     pub created_at: i64,
+    // 0 until the pool is activated
     pub activated_at: i64,
+    // swaps are accepted till this time, 0 until activation if life starts at activation
+    pub matures_at: i64,
     // used for withdrawals
     pub token_a_amount_to_be_distributed: u64,
     pub token_b_amount_to_be_distributed: u64,
     pub profit_of_token_a_amount_to_be_distributed: u64,
     pub profit_of_token_b_amount_to_be_distributed: u64,
+    // investor share of the profits already claimed
+    pub profit_of_token_a_claimed: u64,
+    pub profit_of_token_b_claimed: u64,
+    // swapverse share of the profits already sent to the protocol fee wallet
+    pub protocol_fee_of_token_a_collected: u64,
+    pub protocol_fee_of_token_b_collected: u64,
+    // principal of the flash loan waiting to be repaid in this transaction, 0 if there is none
+    pub flash_loan_amount: u64,
+    pub flash_loan_is_token_a: bool,
+    // sums of the spot prices of token a in token b and of token b in token a, scaled by
+    // PRICE_SCALE, weighted by the seconds they held, wrap around on overflow
+    pub price_a_cumulative: u128,
+    pub price_b_cumulative: u128,
+    pub last_price_update_at: i64,
+    // swaps may not leave the price of token a in token b further than this from 1:1, set by
+    // the admin, 0 turns the guard off
+    pub max_price_deviation_bps: u16,
+    // when enabled the swap fee is set per trade between these bounds by how far the trade
+    // leaves the reserves from balance, in place of swap_fee_bps
+    pub dynamic_fee_enabled: bool,
+    pub min_dynamic_fee_bps: u16,
+    pub max_dynamic_fee_bps: u16,
+    // decimals of the token mints, amounts of both tokens are priced at the precision of the
+    // one with more decimals so that their units are not taken as interchangeable
+    pub token_a_decimals: u8,
+    pub token_b_decimals: u8,
 }
 
 impl SwapPool {
@@ -43,41 +165,694 @@ impl SwapPool {
         token_b_mint: Pubkey,
         pool_share_token_a_mint: Pubkey,
         pool_share_token_b_mint: Pubkey,
+        token_a_decimals: u8,
+        token_b_decimals: u8,
         initial_amount_a: u64,
         initial_amount_b: u64,
-        swap_fee_percentage: u8,
-        swapverse_fee_percentage: u8,
+        curve_type: CurveType,
+        amp_coefficient: u64,
+        fixed_ratio: u64,
+        swap_fee_bps: u16,
+        swapverse_fee_bps: u16,
         min_investment_amount: u64,
         max_days_to_fill: u8,
         swap_life_in_days: u64,
+        life_starts_at: PoolLifeStart,
     ) -> Result<()> {
         self.pool_number = pool_number;
-        self.active_for_swap = false;
-        self.open_for_investment = true;
-        self.open_for_withdrawal = false;
+        self.status = PoolStatus::Funding;
+        self.paused = false;
 
         self.token_a_mint = token_a_mint;
         self.token_b_mint = token_b_mint;
         self.pool_share_token_a_mint = pool_share_token_a_mint;
         self.pool_share_token_b_mint = pool_share_token_b_mint;
+        self.token_a_decimals = token_a_decimals;
+        self.token_b_decimals = token_b_decimals;
 
         self.initial_amount_a = initial_amount_a;
         self.initial_amount_b = initial_amount_b;
 
-        self.swap_fee_percentage = swap_fee_percentage;
-        self.swapverse_fee_percentage = swapverse_fee_percentage;
+        self.curve_type = curve_type;
+        self.amp_coefficient = amp_coefficient;
+        self.fixed_ratio = fixed_ratio;
+
+        self.swap_fee_bps = swap_fee_bps;
+        self.swapverse_fee_bps = swapverse_fee_bps;
 
         self.min_investment_amount = min_investment_amount;
         self.max_days_to_fill = max_days_to_fill;
         self.swap_life_in_days = swap_life_in_days;
+        self.life_starts_at = life_starts_at;
 
         self.created_at = Clock::get()?.unix_timestamp;
-        self.activated_at = i64::MAX;
+        self.activated_at = 0;
+        // pools are activated by their fill deadline at the latest, a life which would not fit
+        // the timestamps from there is refused here rather than at activation
+        self.matures_at = match life_starts_at {
+            PoolLifeStart::CreatedAt => self.life_ends_at(self.created_at)?,
+            PoolLifeStart::ActivatedAt => {
+                self.life_ends_at(self.fill_deadline()?)?;
+                0
+            }
+        };
 
         self.token_a_amount_to_be_distributed = 0;
         self.token_b_amount_to_be_distributed = 0;
         self.profit_of_token_a_amount_to_be_distributed = 0;
         self.profit_of_token_b_amount_to_be_distributed = 0;
+        self.profit_of_token_a_claimed = 0;
+        self.profit_of_token_b_claimed = 0;
+        self.protocol_fee_of_token_a_collected = 0;
+        self.protocol_fee_of_token_b_collected = 0;
+        self.flash_loan_amount = 0;
+        self.flash_loan_is_token_a = false;
+
+        self.price_a_cumulative = 0;
+        self.price_b_cumulative = 0;
+        self.last_price_update_at = 0;
+        self.max_price_deviation_bps = 0;
+        self.dynamic_fee_enabled = false;
+        self.min_dynamic_fee_bps = 0;
+        self.max_dynamic_fee_bps = 0;
+
+        Ok(())
+    }
+
+    // the invariant is only used by the stable swap curve, it is computed when not given
+    pub fn swap_curve(
+        &self,
+        is_token_in_token_a: bool,
+        invariant: Option<u128>,
+    ) -> Box<dyn SwapCurve> {
+        match self.curve_type {
+            CurveType::ConstantProduct => Box::new(ConstantProduct),
+            CurveType::StableSwap => Box::new(StableSwap {
+                amp_coefficient: self.amp_coefficient,
+                invariant,
+            }),
+            CurveType::ConstantSum => Box::new(ConstantSum),
+            CurveType::FixedRatio if is_token_in_token_a => Box::new(FixedRatio {
+                numerator: self.fixed_ratio,
+                denominator: FIXED_RATIO_PRECISION,
+            }),
+            CurveType::FixedRatio => Box::new(FixedRatio {
+                numerator: FIXED_RATIO_PRECISION,
+                denominator: self.fixed_ratio,
+            }),
+        }
+    }
+
+    // invariant of the stable swap curve for the reserves before a swap, computed once and
+    // handed to every pricing of the swap so that its newton iterations are not run again.
+    // none for the other curves
+    pub fn invariant(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        is_token_in_token_a: bool,
+    ) -> Result<Option<u128>> {
+        if self.curve_type != CurveType::StableSwap {
+            return Ok(None);
+        }
+
+        let (reserve_in, reserve_out) =
+            self.normalize_reserves(reserve_in, reserve_out, is_token_in_token_a)?;
+        Ok(Some(compute_d(
+            self.amp_coefficient,
+            &[reserve_in as u128, reserve_out as u128],
+        )?))
+    }
+
+    // factor bringing amounts of a token to the precision of the token with more decimals
+    fn decimals_scale(&self, is_token_a: bool) -> Result<u64> {
+        let decimals = if is_token_a {
+            self.token_a_decimals
+        } else {
+            self.token_b_decimals
+        };
+        let common_decimals = self.token_a_decimals.max(self.token_b_decimals);
+
+        checked(10u64.checked_pow((common_decimals - decimals) as u32))
+    }
+
+    pub fn normalize(&self, amount: u64, is_token_a: bool) -> Result<u64> {
+        checked(amount.checked_mul(self.decimals_scale(is_token_a)?))
+    }
+
+    // back to units of the token, rounded down unless asked otherwise
+    pub fn denormalize(&self, amount: u64, is_token_a: bool, round_up: bool) -> Result<u64> {
+        let scale = self.decimals_scale(is_token_a)?;
+        let denormalized_amount = amount / scale;
+        if round_up && denormalized_amount * scale < amount {
+            return Ok(denormalized_amount + 1);
+        }
+
+        Ok(denormalized_amount)
+    }
+
+    // amount of one token worth the given amount of the other at 1:1, rounded down
+    pub fn convert_amount(&self, amount: u64, is_from_token_a: bool) -> Result<u64> {
+        self.denormalize(
+            self.normalize(amount, is_from_token_a)?,
+            !is_from_token_a,
+            false,
+        )
+    }
+
+    fn normalize_reserves(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        is_token_in_token_a: bool,
+    ) -> Result<(u64, u64)> {
+        Ok((
+            self.normalize(reserve_in, is_token_in_token_a)?,
+            self.normalize(reserve_out, !is_token_in_token_a)?,
+        ))
+    }
+
+    // fee levied on the output of a swap, rounded down
+    pub fn swap_fee_of(&self, output_amount: u64, swap_fee_bps: u16) -> u64 {
+        (output_amount as u128)
+            .checked_mul(swap_fee_bps as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64
+    }
+
+    // output of a swap which leaves at least the given amount to the user once the fee is
+    // levied, rounded up
+    pub fn output_amount_before_fee(&self, user_amount_out: u64, swap_fee_bps: u16) -> Result<u64> {
+        let user_share_bps = (BPS_DENOMINATOR as u128)
+            .checked_sub(swap_fee_bps as u128)
+            .filter(|bps| *bps > 0)
+            .ok_or_else(|| error!(SwapverseError::SwapFeeOutOfBounds))?;
+
+        let output_amount = (user_amount_out as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .unwrap()
+            .checked_add(user_share_bps - 1)
+            .unwrap()
+            .checked_div(user_share_bps)
+            .unwrap();
+        u64::try_from(output_amount).map_err(|_| error!(SwapverseError::MathOverflow))
+    }
+
+    // difference of the reserves as a share of their sum, 0 for a balanced pool and
+    // BPS_DENOMINATOR once one side is empty
+    fn imbalance_bps(reserve_in: u64, reserve_out: u64) -> u128 {
+        let total = reserve_in as u128 + reserve_out as u128;
+        if total == 0 {
+            return 0;
+        }
+
+        (reserve_in.abs_diff(reserve_out) as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .unwrap()
+            .checked_div(total)
+            .unwrap()
+    }
+
+    // fee of a swap taking the whole output from the pool, trades leaving the pool more
+    // imbalanced pay more the further they push it, trades rebalancing it pay the minimum,
+    // all the amounts are normalized
+    pub fn swap_fee_bps_for(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        output_amount: u64,
+    ) -> Result<u16> {
+        if !self.dynamic_fee_enabled {
+            return Ok(self.swap_fee_bps);
+        }
+
+        let imbalance_before = Self::imbalance_bps(reserve_in, reserve_out);
+        let imbalance_after = Self::imbalance_bps(
+            checked(reserve_in.checked_add(amount_in))?,
+            checked(reserve_out.checked_sub(output_amount))?,
+        );
+        if imbalance_after <= imbalance_before {
+            return Ok(self.min_dynamic_fee_bps);
+        }
+
+        let fee_range = (self.max_dynamic_fee_bps - self.min_dynamic_fee_bps) as u128;
+        let fee_increase = fee_range
+            .checked_mul(imbalance_after)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap();
+
+        Ok(self.min_dynamic_fee_bps + fee_increase as u16)
+    }
+
+    // swaps are accepted till maturity and, if the user set one, till their deadline so that
+    // a transaction landing late is not executed at a stale price
+    pub fn check_swap_open(&self, time_now: i64, deadline: Option<i64>) -> Result<()> {
+        require!(time_now <= self.matures_at, SwapverseError::SwapPoolMatured);
+        if let Some(deadline) = deadline {
+            require!(time_now <= deadline, SwapverseError::TransactionExpired);
+        }
+
+        Ok(())
+    }
+
+    // user and treasury shares of the output of an exact input swap, priced against the live
+    // reserves, fees already sent to the treasury are not counted
+    pub fn swap_exact_in(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        is_token_in_token_a: bool,
+        invariant: Option<u128>,
+    ) -> Result<(u64, u64)> {
+        let (reserve_in, reserve_out) =
+            self.normalize_reserves(reserve_in, reserve_out, is_token_in_token_a)?;
+        let normalized_amount_in = self.normalize(amount_in, is_token_in_token_a)?;
+        let normalized_output_amount = self.swap_curve(is_token_in_token_a, invariant).swap_out(
+            normalized_amount_in,
+            reserve_in,
+            reserve_out,
+        )?;
+
+        let swap_fee_bps = self.swap_fee_bps_for(
+            reserve_in,
+            reserve_out,
+            normalized_amount_in,
+            normalized_output_amount,
+        )?;
+        let output_amount =
+            self.denormalize(normalized_output_amount, !is_token_in_token_a, false)?;
+        let treasury_share = self.swap_fee_of(output_amount, swap_fee_bps);
+        let user_share = output_amount.checked_sub(treasury_share).unwrap();
+
+        Ok((user_share, treasury_share))
+    }
+
+    // input and treasury share of an exact output swap, the fee is levied on the output the
+    // same way as for exact input swaps
+    pub fn swap_exact_out(
+        &self,
+        user_amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        is_token_in_token_a: bool,
+        invariant: Option<u128>,
+    ) -> Result<(u64, u64)> {
+        let swap_curve = self.swap_curve(is_token_in_token_a, invariant);
+        let (reserve_in, reserve_out) =
+            self.normalize_reserves(reserve_in, reserve_out, is_token_in_token_a)?;
+
+        // a dynamic fee depends on the size of the trade which depends on the fee, so it is
+        // taken for the trade which would be needed at the highest fee
+        let swap_fee_bps = if self.dynamic_fee_enabled {
+            let output_amount = self.normalize(
+                self.output_amount_before_fee(user_amount_out, self.max_dynamic_fee_bps)?,
+                !is_token_in_token_a,
+            )?;
+            let amount_in = swap_curve.swap_in(output_amount, reserve_in, reserve_out)?;
+            self.swap_fee_bps_for(reserve_in, reserve_out, amount_in, output_amount)?
+        } else {
+            self.swap_fee_bps
+        };
+
+        let output_amount = self.output_amount_before_fee(user_amount_out, swap_fee_bps)?;
+        let normalized_amount_in = swap_curve.swap_in(
+            self.normalize(output_amount, !is_token_in_token_a)?,
+            reserve_in,
+            reserve_out,
+        )?;
+        let amount_in = self.denormalize(normalized_amount_in, is_token_in_token_a, true)?;
+
+        Ok((
+            amount_in,
+            output_amount.checked_sub(user_amount_out).unwrap(),
+        ))
+    }
+
+    // how much worse than the spot price a swap is executed before the fee, in basis points
+    pub fn price_impact_bps(
+        &self,
+        amount_in: u64,
+        output_amount: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        is_token_in_token_a: bool,
+        invariant: Option<u128>,
+    ) -> Result<u64> {
+        if amount_in == 0 {
+            return Ok(0);
+        }
+
+        let (reserve_in, reserve_out) =
+            self.normalize_reserves(reserve_in, reserve_out, is_token_in_token_a)?;
+        let amount_in = self.normalize(amount_in, is_token_in_token_a)?;
+        let output_amount = self.normalize(output_amount, !is_token_in_token_a)?;
+        let spot_price = self
+            .swap_curve(is_token_in_token_a, invariant)
+            .spot_price(reserve_in, reserve_out)?;
+        let execution_price = checked(
+            (output_amount as u128)
+                .checked_mul(PRICE_SCALE)
+                .and_then(|value| value.checked_div(amount_in as u128)),
+        )?;
+        let price_impact = checked(
+            spot_price
+                .saturating_sub(execution_price)
+                .checked_mul(BPS_DENOMINATOR as u128)
+                .and_then(|value| value.checked_div(spot_price)),
+        )?;
+
+        Ok(price_impact as u64)
+    }
+
+    // the fee is levied on the token going out, so it is profit of that token
+    pub fn record_swap_fee(&mut self, is_token_in_token_a: bool, treasury_amount: u64) {
+        self.record_profit(!is_token_in_token_a, treasury_amount);
+    }
+
+    pub fn record_profit(&mut self, is_token_a: bool, profit_amount: u64) {
+        if is_token_a {
+            self.profit_of_token_a_amount_to_be_distributed = self
+                .profit_of_token_a_amount_to_be_distributed
+                .checked_add(profit_amount)
+                .unwrap();
+        } else {
+            self.profit_of_token_b_amount_to_be_distributed = self
+                .profit_of_token_b_amount_to_be_distributed
+                .checked_add(profit_amount)
+                .unwrap();
+        }
+    }
+
+    // fee charged on a flash loan, rounded up
+    pub fn flash_loan_fee_of(&self, loan_amount: u64) -> u64 {
+        (loan_amount as u128)
+            .checked_mul(FLASH_LOAN_FEE_BPS as u128)
+            .unwrap()
+            .checked_add(BPS_DENOMINATOR as u128 - 1)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64
+    }
+
+    // price accumulators as they would be at the given time, the reserves should be the ones
+    // since the last update
+    pub fn price_cumulatives_at(
+        &self,
+        time_now: i64,
+        reserve_a: u64,
+        reserve_b: u64,
+        invariant: Option<u128>,
+    ) -> Result<(u128, u128)> {
+        let elapsed = time_now.saturating_sub(self.last_price_update_at);
+        if elapsed <= 0 || reserve_a == 0 || reserve_b == 0 {
+            return Ok((self.price_a_cumulative, self.price_b_cumulative));
+        }
+
+        let (reserve_a, reserve_b) = self.normalize_reserves(reserve_a, reserve_b, true)?;
+        let price_a = self
+            .swap_curve(true, invariant)
+            .spot_price(reserve_a, reserve_b)?;
+        let price_b = self
+            .swap_curve(false, invariant)
+            .spot_price(reserve_b, reserve_a)?;
+
+        Ok((
+            self.price_a_cumulative
+                .wrapping_add(price_a.wrapping_mul(elapsed as u128)),
+            self.price_b_cumulative
+                .wrapping_add(price_b.wrapping_mul(elapsed as u128)),
+        ))
+    }
+
+    // has to run before the reserves change, so that the price which held till now is recorded
+    pub fn update_price_accumulators(
+        &mut self,
+        time_now: i64,
+        reserve_a: u64,
+        reserve_b: u64,
+        invariant: Option<u128>,
+    ) -> Result<()> {
+        let (price_a_cumulative, price_b_cumulative) =
+            self.price_cumulatives_at(time_now, reserve_a, reserve_b, invariant)?;
+
+        self.price_a_cumulative = price_a_cumulative;
+        self.price_b_cumulative = price_b_cumulative;
+        self.last_price_update_at = time_now;
+
+        Ok(())
+    }
+
+    // time weighted average price between two observations of a price accumulator, scaled by
+    // PRICE_SCALE
+    pub fn twap(
+        start_price_cumulative: u128,
+        start_time: i64,
+        end_price_cumulative: u128,
+        end_time: i64,
+    ) -> Result<u128> {
+        require!(end_time > start_time, SwapverseError::InvalidTwapWindow);
+
+        Ok(end_price_cumulative.wrapping_sub(start_price_cumulative)
+            / (end_time - start_time) as u128)
+    }
+
+    // distance of the spot price of token a in token b from 1:1, in basis points
+    pub fn price_deviation_bps(
+        &self,
+        reserve_a: u64,
+        reserve_b: u64,
+        invariant: Option<u128>,
+    ) -> Result<u128> {
+        let (reserve_a, reserve_b) = self.normalize_reserves(reserve_a, reserve_b, true)?;
+        let price = self
+            .swap_curve(true, invariant)
+            .spot_price(reserve_a, reserve_b)?;
+
+        checked(
+            price
+                .abs_diff(PRICE_SCALE)
+                .checked_mul(BPS_DENOMINATOR as u128)
+                .and_then(|value| value.checked_div(PRICE_SCALE)),
+        )
+    }
+
+    // rejects swaps which leave the price outside the depeg guard band, swaps moving the price
+    // back towards 1:1 are still accepted so that a depegged pool can recover. a swap keeps the
+    // invariant, so the one of the reserves before it prices the reserves after it too
+    pub fn check_depeg_guard(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        amount_out: u64,
+        is_token_in_token_a: bool,
+        invariant: Option<u128>,
+    ) -> Result<()> {
+        if self.max_price_deviation_bps == 0 {
+            return Ok(());
+        }
+
+        let new_reserve_in = checked(reserve_in.checked_add(amount_in))?;
+        let new_reserve_out = checked(reserve_out.checked_sub(amount_out))?;
+        let (deviation_before, deviation_after) = if is_token_in_token_a {
+            (
+                self.price_deviation_bps(reserve_in, reserve_out, invariant)?,
+                self.price_deviation_bps(new_reserve_in, new_reserve_out, invariant)?,
+            )
+        } else {
+            (
+                self.price_deviation_bps(reserve_out, reserve_in, invariant)?,
+                self.price_deviation_bps(new_reserve_out, new_reserve_in, invariant)?,
+            )
+        };
+
+        require!(
+            deviation_after <= self.max_price_deviation_bps as u128
+                || deviation_after < deviation_before,
+            SwapverseError::PriceOutsideDepegGuard
+        );
+
+        Ok(())
+    }
+
+    // the only place where the status of a pool is changed
+    pub fn transition_to(&mut self, next_status: PoolStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next_status),
+            SwapverseError::InvalidPoolStatusTransition
+        );
+
+        if next_status == PoolStatus::Active {
+            self.activated_at = Clock::get()?.unix_timestamp;
+            self.last_price_update_at = self.activated_at;
+            if self.life_starts_at == PoolLifeStart::ActivatedAt {
+                self.matures_at = self.life_ends_at(self.activated_at)?;
+            }
+        }
+        self.status = next_status;
+
+        Ok(())
+    }
+
+    // investments are accepted till this time
+    pub fn fill_deadline(&self) -> Result<i64> {
+        days_after(self.created_at, self.max_days_to_fill as u64)
+    }
+
+    fn life_ends_at(&self, life_started_at: i64) -> Result<i64> {
+        days_after(life_started_at, self.swap_life_in_days)
+    }
+
+    // moves a pool past its fill deadline or maturity to withdrawals and snapshots the
+    // pool balances to be distributed, returns false if the pool was not due
+    pub fn settle(
+        &mut self,
+        time_now: i64,
+        pool_token_a_amount: u64,
+        pool_token_b_amount: u64,
+    ) -> Result<bool> {
+        let next_status = match self.status {
+            PoolStatus::Funding if time_now > self.fill_deadline()? => PoolStatus::ExpiredUnfilled,
+            PoolStatus::Active if time_now > self.matures_at => PoolStatus::Matured,
+            _ => return Ok(false),
+        };
+
+        self.transition_to(next_status)?;
+        self.token_a_amount_to_be_distributed = pool_token_a_amount;
+        self.token_b_amount_to_be_distributed = pool_token_b_amount;
+
+        Ok(true)
+    }
+
+    // value of the deposits of one side of a matured pool at 1:1, in units of its token, the
+    // distributed balances are shared out between both sides by their initial amounts
+    fn matured_value_of(&self, is_token_a: bool) -> Result<u64> {
+        let distributed_value = self.normalize(self.token_a_amount_to_be_distributed, true)?
+            as u128
+            + self.normalize(self.token_b_amount_to_be_distributed, false)? as u128;
+        let initial_value = self.normalize(self.initial_amount_a, true)? as u128
+            + self.normalize(self.initial_amount_b, false)? as u128;
+        let initial_amount = if is_token_a {
+            self.initial_amount_a
+        } else {
+            self.initial_amount_b
+        };
+
+        let value = checked(
+            (self.normalize(initial_amount, is_token_a)? as u128)
+                .checked_mul(distributed_value)
+                .and_then(|value| value.checked_div(initial_value)),
+        )?;
+        self.denormalize(
+            u64::try_from(value).map_err(|_| error!(SwapverseError::MathOverflow))?,
+            is_token_a,
+            false,
+        )
+    }
+
+    // amounts of the token of the shares and of the other token paid for redeeming pool
+    // shares. in a matured pool each side takes its own token up to the value of its deposits
+    // and the part of the other token held beyond the value of the other side, otherwise the
+    // deposits are given back
+    pub fn withdrawal_amounts(
+        &self,
+        pool_share_amount: u64,
+        is_token_a: bool,
+    ) -> Result<(u64, u64)> {
+        if self.status != PoolStatus::Matured {
+            return Ok((pool_share_amount, 0));
+        }
+
+        let (distributed_amount, other_distributed_amount, initial_amount) = if is_token_a {
+            (
+                self.token_a_amount_to_be_distributed,
+                self.token_b_amount_to_be_distributed,
+                self.initial_amount_a,
+            )
+        } else {
+            (
+                self.token_b_amount_to_be_distributed,
+                self.token_a_amount_to_be_distributed,
+                self.initial_amount_b,
+            )
+        };
+        let side_amount = distributed_amount.min(self.matured_value_of(is_token_a)?);
+        let other_side_amount =
+            other_distributed_amount.saturating_sub(self.matured_value_of(!is_token_a)?);
+
+        let pro_rata = |amount: u64| -> Result<u64> {
+            let share = checked(
+                (amount as u128)
+                    .checked_mul(pool_share_amount as u128)
+                    .and_then(|value| value.checked_div(initial_amount as u128)),
+            )?;
+            u64::try_from(share).map_err(|_| error!(SwapverseError::MathOverflow))
+        };
+
+        Ok((pro_rata(side_amount)?, pro_rata(other_side_amount)?))
+    }
+
+    // part of the recorded profit of a token which belongs to swapverse
+    pub fn protocol_share_of_profit(&self, is_token_a: bool) -> u64 {
+        let profit_amount = if is_token_a {
+            self.profit_of_token_a_amount_to_be_distributed
+        } else {
+            self.profit_of_token_b_amount_to_be_distributed
+        };
+
+        (profit_amount as u128)
+            .checked_mul(self.swapverse_fee_bps as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128)
+            .unwrap() as u64
+    }
+
+    // protocol share of a token which is still lying in the pool treasury
+    pub fn uncollected_protocol_fee(&self, is_token_a: bool) -> u64 {
+        let collected_amount = if is_token_a {
+            self.protocol_fee_of_token_a_collected
+        } else {
+            self.protocol_fee_of_token_b_collected
+        };
+
+        self.protocol_share_of_profit(is_token_a)
+            .checked_sub(collected_amount)
+            .unwrap()
+    }
+
+    // profit of a token recorded by swaps which has not been paid out to investors or
+    // swapverse yet, should always be held by the treasury of that token
+    pub fn unpaid_profit(&self, is_token_a: bool) -> u64 {
+        let (profit_amount, claimed_amount, collected_amount) = if is_token_a {
+            (
+                self.profit_of_token_a_amount_to_be_distributed,
+                self.profit_of_token_a_claimed,
+                self.protocol_fee_of_token_a_collected,
+            )
+        } else {
+            (
+                self.profit_of_token_b_amount_to_be_distributed,
+                self.profit_of_token_b_claimed,
+                self.protocol_fee_of_token_b_collected,
+            )
+        };
+
+        profit_amount
+            .checked_sub(claimed_amount)
+            .unwrap()
+            .checked_sub(collected_amount)
+            .unwrap()
+    }
+
+    pub fn reconcile_treasury(&self, is_token_a: bool, treasury_balance: u64) -> Result<()> {
+        require!(
+            self.unpaid_profit(is_token_a) <= treasury_balance,
+            SwapverseError::TreasuryBalanceMismatch
+        );
 
         Ok(())
     }
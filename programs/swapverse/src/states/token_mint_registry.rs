@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// one account per stable coin mint that pools are allowed to use
+#[account]
+#[derive(Default)]
+pub struct TokenMintRegistry {
+    pub mint: Pubkey,
+    pub registered_at: i64,
+}
+
+impl TokenMintRegistry {
+    pub fn initialize(&mut self, mint: Pubkey) -> Result<()> {
+        self.mint = mint;
+        self.registered_at = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { BN } from "bn.js";
+import { assert } from "chai";
 
 describe("swapverse", () => {
   // Configure the client to use the local cluster.
@@ -33,6 +34,9 @@ describe("swapverse", () => {
   let pai_dev_mint: anchor.web3.PublicKey;
   let usdh_dev_mint: anchor.web3.PublicKey;
 
+  let usdc_dev_mint_registry: anchor.web3.PublicKey;
+  let usdt_dev_mint_registry: anchor.web3.PublicKey;
+
   const investor1 = anchor.web3.Keypair.generate();
   const investor2 = anchor.web3.Keypair.generate();
   const user1 = anchor.web3.Keypair.generate();
@@ -148,6 +152,26 @@ describe("swapverse", () => {
       );
     usdh_dev_mint = usdh_dev_mint_add;
 
+    const [
+      usdc_dev_mint_registry_add,
+      usdt_dev_mint_registry_add,
+      uxd_dev_mint_registry,
+      pai_dev_mint_registry,
+      usdh_dev_mint_registry,
+    ] = await Promise.all(
+      [usdc_dev_mint, usdt_dev_mint, uxd_dev_mint, pai_dev_mint, usdh_dev_mint].map(
+        async (mint) =>
+          (
+            await anchor.web3.PublicKey.findProgramAddress(
+              [mint.toBuffer(), Buffer.from("token-mint-registry")],
+              program.programId
+            )
+          )[0]
+      )
+    );
+    usdc_dev_mint_registry = usdc_dev_mint_registry_add;
+    usdt_dev_mint_registry = usdt_dev_mint_registry_add;
+
     const tx = await program.methods
       .initializeGlobalState()
       .accounts({
@@ -159,6 +183,11 @@ describe("swapverse", () => {
         uxdTokenMint: uxd_dev_mint,
         paiTokenMint: pai_dev_mint,
         usdhTokenMint: usdh_dev_mint,
+        usdcTokenMintRegistry: usdc_dev_mint_registry,
+        usdtTokenMintRegistry: usdt_dev_mint_registry,
+        uxdTokenMintRegistry: uxd_dev_mint_registry,
+        paiTokenMintRegistry: pai_dev_mint_registry,
+        usdhTokenMintRegistry: usdh_dev_mint_registry,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -167,6 +196,75 @@ describe("swapverse", () => {
     console.log("Your transaction signature", tx);
   });
 
+  it("Transfers admin in two steps", async () => {
+    const new_admin = anchor.web3.Keypair.generate();
+
+    let tx = await program.methods
+      .proposeAdmin(new_admin.publicKey)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+      })
+      .rpc();
+    console.log("Your transaction signature", tx);
+
+    let tx2 = await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: new_admin.publicKey,
+        globalState: global_state,
+      })
+      .signers([new_admin])
+      .rpc();
+    console.log("Your transaction signature", tx2);
+
+    // hand the role back so the rest of the suite can keep using the wallet
+    let tx3 = await program.methods
+      .proposeAdmin(wallet.publicKey)
+      .accounts({
+        admin: new_admin.publicKey,
+        globalState: global_state,
+      })
+      .signers([new_admin])
+      .rpc();
+    console.log("Your transaction signature", tx3);
+
+    let tx4 = await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: wallet.publicKey,
+        globalState: global_state,
+      })
+      .rpc();
+    console.log("Your transaction signature", tx4);
+  });
+
+  it("Registers the dev token mints at initialization", async () => {
+    const usdc_dev_mint_registry_state =
+      await program.account.tokenMintRegistry.fetch(usdc_dev_mint_registry);
+    assert.isTrue(usdc_dev_mint_registry_state.mint.equals(usdc_dev_mint));
+
+    // a mint is registered only once
+    let registered_again = false;
+    try {
+      await program.methods
+        .registerTokenMint()
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          tokenMint: usdt_dev_mint,
+          tokenMintRegistry: usdt_dev_mint_registry,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      registered_again = true;
+    } catch (e) {
+      // the system program refuses to allocate the registry account already in use
+      assert.include(e.toString(), "0x0");
+    }
+    assert.isFalse(registered_again);
+  });
+
   it("Gets test tokens", async () => {
     // Initialise ATA
     investor1_usdc_ata = await getOrCreateAssociatedTokenAccount(
@@ -225,6 +323,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdc_dev_mint,
+        tokenMintRegistry: usdc_dev_mint_registry,
         investorTokenAccount: investor1_usdc_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -241,6 +340,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdt_dev_mint,
+        tokenMintRegistry: usdt_dev_mint_registry,
         investorTokenAccount: investor1_usdt_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -257,6 +357,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdc_dev_mint,
+        tokenMintRegistry: usdc_dev_mint_registry,
         investorTokenAccount: investor2_usdc_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -273,6 +374,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdt_dev_mint,
+        tokenMintRegistry: usdt_dev_mint_registry,
         investorTokenAccount: investor2_usdt_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -289,6 +391,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdc_dev_mint,
+        tokenMintRegistry: usdc_dev_mint_registry,
         investorTokenAccount: user1_usdc_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -305,6 +408,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdt_dev_mint,
+        tokenMintRegistry: usdt_dev_mint_registry,
         investorTokenAccount: user1_usdt_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -321,6 +425,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdc_dev_mint,
+        tokenMintRegistry: usdc_dev_mint_registry,
         investorTokenAccount: user2_usdc_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -337,6 +442,7 @@ describe("swapverse", () => {
         globalState: global_state,
         signingAuthority: signing_authority,
         tokenMint: usdt_dev_mint,
+        tokenMintRegistry: usdt_dev_mint_registry,
         investorTokenAccount: user2_usdt_ata.address,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -347,6 +453,18 @@ describe("swapverse", () => {
     console.log("Your transaction signature", tx6);
   });
 
+  it("Updates fee config", async () => {
+    const tx = await program.methods
+      .updateFeeConfig(0, 1000, 500, 5000)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+      })
+      .rpc();
+
+    console.log("Your transaction signature", tx);
+  });
+
   it("Creates swap pool", async () => {
     const swap_pool_num = new BN(0).toArrayLike(Buffer, "le", 8);
     let [swap_pool_add, swap_pool_b] =
@@ -387,11 +505,15 @@ describe("swapverse", () => {
       .createSwapPool(
         initial_amount_a,
         initial_amount_a,
-        10,
-        10,
+        { constantProduct: {} },
+        new BN(0),
+        new BN(0),
+        1000,
+        1000,
         min_amount,
         30,
-        life
+        life,
+        { activatedAt: {} }
       )
       .accounts({
         owner: wallet.publicKey,
@@ -400,6 +522,8 @@ describe("swapverse", () => {
         swapPool: swap_pool,
         tokenAMint: usdc_dev_mint,
         tokenBMint: usdt_dev_mint,
+        tokenAMintRegistry: usdc_dev_mint_registry,
+        tokenBMintRegistry: usdt_dev_mint_registry,
         poolShareTokenAMint: pool_share_token_a_mint,
         poolShareTokenBMint: pool_share_token_b_mint,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -579,7 +703,7 @@ describe("swapverse", () => {
     let amount = new BN(10_000);
     let min_amount_out = new BN(8_000);
     let tx = await program.methods
-      .swapToken(amount, min_amount_out, true)
+      .swapToken(amount, min_amount_out, true, null, 0)
       .accounts({
         user: user1.publicKey,
         globalState: global_state,
@@ -603,7 +727,7 @@ describe("swapverse", () => {
     amount = new BN(10_000);
     min_amount_out = new BN(6_000);
     let tx2 = await program.methods
-      .swapToken(amount, min_amount_out, true)
+      .swapToken(amount, min_amount_out, true, null, 0)
       .accounts({
         user: user1.publicKey,
         globalState: global_state,
@@ -627,7 +751,7 @@ describe("swapverse", () => {
     amount = new BN(10_000);
     min_amount_out = new BN(6_000);
     let tx3 = await program.methods
-      .swapToken(amount, min_amount_out, false)
+      .swapToken(amount, min_amount_out, false, null, 0)
       .accounts({
         user: user1.publicKey,
         globalState: global_state,
@@ -651,7 +775,7 @@ describe("swapverse", () => {
     amount = new BN(10_000);
     min_amount_out = new BN(6_000);
     let tx4 = await program.methods
-      .swapToken(amount, min_amount_out, false)
+      .swapToken(amount, min_amount_out, false, null, 0)
       .accounts({
         user: user1.publicKey,
         globalState: global_state,
@@ -673,86 +797,1975 @@ describe("swapverse", () => {
     console.log("Your transaction signature is ", tx4);
   });
 
-  it("claims profit", async () => {
+  it("swaps for an exact output", async () => {
+    const amount_out = 1_000;
+    const balance_before = await getAccount(
+      provider.connection,
+      user1_usdt_ata.address
+    );
+
     let tx = await program.methods
-      .claimProfit()
+      .swapTokenExactOut(new BN(amount_out), new BN(2_000), true, null)
       .accounts({
-        investor: investor1.publicKey,
+        user: user1.publicKey,
         globalState: global_state,
         signingAuthority: signing_authority,
         swapPool: swap_pool,
-        withdrawTokenMint: usdc_dev_mint,
         tokenAMint: usdc_dev_mint,
         tokenBMint: usdt_dev_mint,
+        userTokenAAccount: user1_usdc_ata.address,
+        userTokenBAccount: user1_usdt_ata.address,
+        swapPoolTokenAAccount: swap_pool_usdc_ata,
+        swapPoolTokenBAccount: swap_pool_usdt_ata,
         swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
         swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
-        investorTokenAccount: investor1_usdc_ata.address,
-        poolShareTokenMint: pool_share_token_a_mint,
-        investorPoolShareTokenAccount: investor1_pool_share_token_a_ata.address,
-        investorPoolInfo: investor1_pool_info,
         tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
-      .signers([investor1])
+      .signers([user1])
       .rpc();
     console.log("Your transaction signature is ", tx);
 
-    let tx3 = await program.methods
-      .claimProfit()
+    const balance_after = await getAccount(
+      provider.connection,
+      user1_usdt_ata.address
+    );
+    assert.equal(
+      Number(balance_after.amount) - Number(balance_before.amount),
+      amount_out
+    );
+  });
+
+  it("quotes the same output as the swap", async () => {
+    const amount = new BN(2_500);
+    const quote = await program.methods
+      .quoteSwap(amount, false)
       .accounts({
-        investor: investor2.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+        swapPoolTokenAAccount: swap_pool_usdc_ata,
+        swapPoolTokenBAccount: swap_pool_usdt_ata,
+      })
+      .view();
+
+    const balance_before = await getAccount(
+      provider.connection,
+      user1_usdc_ata.address
+    );
+    let tx = await program.methods
+      .swapToken(amount, quote.amountOut, false, null, 0)
+      .accounts({
+        user: user1.publicKey,
         globalState: global_state,
         signingAuthority: signing_authority,
         swapPool: swap_pool,
-        withdrawTokenMint: usdc_dev_mint,
         tokenAMint: usdc_dev_mint,
         tokenBMint: usdt_dev_mint,
+        userTokenAAccount: user1_usdc_ata.address,
+        userTokenBAccount: user1_usdt_ata.address,
+        swapPoolTokenAAccount: swap_pool_usdc_ata,
+        swapPoolTokenBAccount: swap_pool_usdt_ata,
         swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
         swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
-        investorTokenAccount: investor2_usdc_ata.address,
-        poolShareTokenMint: pool_share_token_a_mint,
-        investorPoolShareTokenAccount: investor2_pool_share_token_a_ata.address,
-        investorPoolInfo: investor2_pool_info,
         tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
-      .signers([investor2])
+      .signers([user1])
       .rpc();
-    console.log("Your transaction signature is ", tx3);
+    console.log("Your transaction signature is ", tx);
+
+    const balance_after = await getAccount(
+      provider.connection,
+      user1_usdc_ata.address
+    );
+    assert.equal(
+      Number(balance_after.amount) - Number(balance_before.amount),
+      quote.amountOut.toNumber()
+    );
+    assert.isTrue(quote.fee.gtn(0));
   });
 
-  // following lines should be added at the end of check_for_withdrawal_open function in withdraw_swap_pool.rs
-  // NOTE: dangerous to add them for live product
-  // self.swap_pool.open_for_investment = false;
-  // self.swap_pool.open_for_withdrawal = true;
-  // self.set_withdrawable_values();
-  it.skip("withdraws from swap pool", async () => {
+  it("never decreases the invariant across swaps", async () => {
+    const pool_invariant = async () => {
+      const token_a_account = await getAccount(
+        provider.connection,
+        swap_pool_usdc_ata
+      );
+      const token_b_account = await getAccount(
+        provider.connection,
+        swap_pool_usdt_ata
+      );
+      return new BN(token_a_account.amount.toString()).mul(
+        new BN(token_b_account.amount.toString())
+      );
+    };
+
+    let invariant = await pool_invariant();
+    for (const [amount, is_token_in_token_a] of [
+      [5_000, true],
+      [1_234, false],
+      [7, true],
+      [9_999, false],
+      [3_333, false],
+      [1, true],
+    ] as [number, boolean][]) {
+      let tx = await program.methods
+        .swapToken(new BN(amount), new BN(0), is_token_in_token_a, null, 0)
+        .accounts({
+          user: user2.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: swap_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: user2_usdc_ata.address,
+          userTokenBAccount: user2_usdt_ata.address,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+          swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+          swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      console.log("Your transaction signature is ", tx);
+
+      const new_invariant = await pool_invariant();
+      assert.isTrue(new_invariant.gte(invariant));
+      invariant = new_invariant;
+    }
+  });
+
+  it("accumulates a time weighted price", async () => {
+    const swap = async () => {
+      let tx = await program.methods
+        .swapToken(new BN(100), new BN(0), true, null, 0)
+        .accounts({
+          user: user2.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: swap_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: user2_usdc_ata.address,
+          userTokenBAccount: user2_usdt_ata.address,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+          swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+          swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      console.log("Your transaction signature is ", tx);
+      return await program.account.swapPool.fetch(swap_pool);
+    };
+
+    const start = await swap();
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    const end = await swap();
+
+    const elapsed = end.lastPriceUpdateAt.sub(start.lastPriceUpdateAt);
+    assert.isTrue(elapsed.gtn(0));
+    // prices are scaled by 10^12, both tokens of the pool are dollar stable coins
+    const twap_a = end.priceACumulative.sub(start.priceACumulative).div(elapsed);
+    const twap_b = end.priceBCumulative.sub(start.priceBCumulative).div(elapsed);
+    assert.isTrue(twap_a.gt(new BN(500_000_000_000)));
+    assert.isTrue(twap_b.gt(new BN(500_000_000_000)));
+  });
+
+  it("routes a swap through several hops", async () => {
+    // usdc -> usdt -> usdc through the same pool, each hop pays its own fee
+    const hop = (
+      pool_token_in: anchor.web3.PublicKey,
+      pool_token_out: anchor.web3.PublicKey,
+      treasury_token_out: anchor.web3.PublicKey,
+      user_token_out: anchor.web3.PublicKey
+    ) =>
+      [swap_pool, pool_token_in, pool_token_out, treasury_token_out, user_token_out].map(
+        (pubkey) => ({ pubkey, isSigner: false, isWritable: true })
+      );
+
+    const balance_before = await getAccount(
+      provider.connection,
+      user1_usdc_ata.address
+    );
+
+    const amount = 1_000;
     let tx = await program.methods
-      .withdrawSwapPool(true)
+      .swapRoute(new BN(amount), new BN(1), null)
       .accounts({
-        investor: investor1.publicKey,
+        user: user1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        userTokenInAccount: user1_usdc_ata.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        ...hop(
+          swap_pool_usdc_ata,
+          swap_pool_usdt_ata,
+          swap_pool_treasury_token_b_ata,
+          user1_usdt_ata.address
+        ),
+        ...hop(
+          swap_pool_usdt_ata,
+          swap_pool_usdc_ata,
+          swap_pool_treasury_token_a_ata,
+          user1_usdc_ata.address
+        ),
+      ])
+      .signers([user1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const balance_after = await getAccount(
+      provider.connection,
+      user1_usdc_ata.address
+    );
+    const spent = Number(balance_before.amount) - Number(balance_after.amount);
+    assert.isTrue(spent > 0 && spent < amount);
+  });
+
+  it("lends from the pool reserves within one transaction", async () => {
+    const loan_accounts = {
+      borrower: user1.publicKey,
+      globalState: global_state,
+      signingAuthority: signing_authority,
+      swapPool: swap_pool,
+      tokenMint: usdc_dev_mint,
+      swapPoolTokenAccount: swap_pool_usdc_ata,
+      borrowerTokenAccount: user1_usdc_ata.address,
+      instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const repay_instruction = await program.methods
+      .flashRepay()
+      .accounts({
+        swapPool: swap_pool,
+        borrower: user1.publicKey,
+        signingAuthority: signing_authority,
+        tokenMint: usdc_dev_mint,
+        swapPoolTokenAccount: swap_pool_usdc_ata,
+        swapPoolTreasuryTokenAccount: swap_pool_treasury_token_a_ata,
+        borrowerTokenAccount: user1_usdc_ata.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+
+    const pool_before = await getAccount(
+      provider.connection,
+      swap_pool_usdc_ata
+    );
+    const treasury_before = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_a_ata
+    );
+
+    let tx = await program.methods
+      .flashLoan(new BN(10_000))
+      .accounts(loan_accounts)
+      .postInstructions([repay_instruction])
+      .signers([user1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const pool_after = await getAccount(
+      provider.connection,
+      swap_pool_usdc_ata
+    );
+    const treasury_after = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_a_ata
+    );
+    assert.equal(pool_after.amount, pool_before.amount);
+    assert.equal(
+      Number(treasury_after.amount) - Number(treasury_before.amount),
+      9
+    );
+
+    let borrowed = false;
+    try {
+      await program.methods
+        .flashLoan(new BN(10_000))
+        .accounts(loan_accounts)
+        .signers([user1])
+        .rpc();
+      borrowed = true;
+    } catch (e) {
+      assert.include(e.toString(), "FlashRepayMissing");
+    }
+    assert.isFalse(borrowed);
+  });
+
+  it("pays a slice of the swap fee to the referrer", async () => {
+    const referrer = anchor.web3.Keypair.generate();
+    const referrer_usdt_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user1,
+      usdt_dev_mint,
+      referrer.publicKey
+    );
+    const treasury_before = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_b_ata
+    );
+
+    let tx = await program.methods
+      .swapToken(new BN(5_000), new BN(0), true, null, 1_000)
+      .accounts({
+        user: user1.publicKey,
         globalState: global_state,
         signingAuthority: signing_authority,
         swapPool: swap_pool,
         tokenAMint: usdc_dev_mint,
         tokenBMint: usdt_dev_mint,
+        userTokenAAccount: user1_usdc_ata.address,
+        userTokenBAccount: user1_usdt_ata.address,
         swapPoolTokenAAccount: swap_pool_usdc_ata,
         swapPoolTokenBAccount: swap_pool_usdt_ata,
-        investorTokenAAccount: investor1_usdc_ata.address,
-        investorTokenBAccount: investor1_usdt_ata.address,
-        poolShareTokenAMint: pool_share_token_a_mint,
-        poolShareTokenBMint: pool_share_token_b_mint,
-        investorPoolShareTokenAAccount: investor1_pool_share_token_a_ata.address,
-        investorPoolShareTokenBAccount: investor1_pool_share_token_b_ata.address,
-        investorPoolInfo: investor1_pool_info,
+        swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+        swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
         tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
-      .signers([investor1])
+      .remainingAccounts([
+        {
+          pubkey: referrer_usdt_ata.address,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .signers([user1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    // 10% of the fee goes to the referrer, the treasury keeps the rest
+    const referrer_account = await getAccount(
+      provider.connection,
+      referrer_usdt_ata.address
+    );
+    const treasury_after = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_b_ata
+    );
+    const fee =
+      Number(treasury_after.amount) -
+      Number(treasury_before.amount) +
+      Number(referrer_account.amount);
+    assert.isTrue(Number(referrer_account.amount) > 0);
+    assert.equal(Number(referrer_account.amount), Math.floor(fee / 10));
+
+    // the swapper cannot refer itself
+    let self_referred = false;
+    try {
+      await program.methods
+        .swapToken(new BN(5_000), new BN(0), true, null, 1_000)
+        .accounts({
+          user: user1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: swap_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: user1_usdc_ata.address,
+          userTokenBAccount: user1_usdt_ata.address,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+          swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+          swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          {
+            pubkey: user1_usdt_ata.address,
+            isSigner: false,
+            isWritable: true,
+          },
+        ])
+        .signers([user1])
+        .rpc();
+      self_referred = true;
+    } catch (e) {
+      assert.include(e.toString(), "SelfReferral");
+    }
+    assert.isFalse(self_referred);
+  });
+
+  it("charges less for swaps rebalancing the pool", async () => {
+    let tx = await program.methods
+      .setDynamicFee(true, 1, 1000)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const quote = async (is_token_in_token_a: boolean) =>
+      await program.methods
+        .quoteSwap(new BN(1_000), is_token_in_token_a)
+        .accounts({
+          globalState: global_state,
+          swapPool: swap_pool,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+        })
+        .view();
+
+    const reserve_a = await getAccount(provider.connection, swap_pool_usdc_ata);
+    const reserve_b = await getAccount(provider.connection, swap_pool_usdt_ata);
+    // adding the token the pool has less of moves it back towards balance
+    const is_rebalancing_token_a = reserve_a.amount < reserve_b.amount;
+    const rebalancing_quote = await quote(is_rebalancing_token_a);
+    const imbalancing_quote = await quote(!is_rebalancing_token_a);
+    assert.isTrue(rebalancing_quote.fee.lt(imbalancing_quote.fee));
+
+    let tx2 = await program.methods
+      .setDynamicFee(false, 0, 0)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx2);
+  });
+
+  it("rejects swaps past their deadline", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) - 60);
+
+    let swapped = false;
+    try {
+      await program.methods
+        .swapToken(new BN(1_000), new BN(0), true, deadline, 0)
+        .accounts({
+          user: user1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: swap_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: user1_usdc_ata.address,
+          userTokenBAccount: user1_usdt_ata.address,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+          swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+          swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+      swapped = true;
+    } catch (e) {
+      assert.include(e.toString(), "TransactionExpired");
+    }
+    assert.isFalse(swapped);
+  });
+
+  it("rejects swaps leaving the depeg guard band", async () => {
+    let tx = await program.methods
+      .setPoolDepegGuard(1)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    // a swap of this size moves the price by far more than 1 bps, at least the direction
+    // moving it away from 1:1 has to be rejected
+    let rejected = 0;
+    for (const is_token_in_token_a of [true, false]) {
+      try {
+        await program.methods
+          .swapToken(new BN(5_000), new BN(0), is_token_in_token_a, null, 0)
+          .accounts({
+            user: user2.publicKey,
+            globalState: global_state,
+            signingAuthority: signing_authority,
+            swapPool: swap_pool,
+            tokenAMint: usdc_dev_mint,
+            tokenBMint: usdt_dev_mint,
+            userTokenAAccount: user2_usdc_ata.address,
+            userTokenBAccount: user2_usdt_ata.address,
+            swapPoolTokenAAccount: swap_pool_usdc_ata,
+            swapPoolTokenBAccount: swap_pool_usdt_ata,
+            swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+            swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([user2])
+          .rpc();
+      } catch (e) {
+        assert.include(e.toString(), "PriceOutsideDepegGuard");
+        rejected += 1;
+      }
+    }
+    assert.isTrue(rejected > 0);
+
+    let tx2 = await program.methods
+      .setPoolDepegGuard(0)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx2);
+  });
+
+  it("rejects swaps while the pool is paused", async () => {
+    let tx = await program.methods
+      .setPoolPause(true)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
       .rpc();
     console.log("Your transaction signature is ", tx);
+
+    let swapped = false;
+    try {
+      await program.methods
+        .swapToken(new BN(1_000), new BN(0), true, null, 0)
+        .accounts({
+          user: user1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: swap_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: user1_usdc_ata.address,
+          userTokenBAccount: user1_usdt_ata.address,
+          swapPoolTokenAAccount: swap_pool_usdc_ata,
+          swapPoolTokenBAccount: swap_pool_usdt_ata,
+          swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+          swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+      swapped = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolPaused");
+    }
+    assert.isFalse(swapped);
+
+    let tx2 = await program.methods
+      .setPoolPause(false)
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        swapPool: swap_pool,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx2);
+  });
+
+  it("claims profit", async () => {
+    let tx = await program.methods
+      .claimProfit()
+      .accounts({
+        investor: investor1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: swap_pool,
+        withdrawTokenMint: usdc_dev_mint,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+        swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+        investorTokenAccount: investor1_usdc_ata.address,
+        poolShareTokenMint: pool_share_token_a_mint,
+        investorPoolShareTokenAccount: investor1_pool_share_token_a_ata.address,
+        investorPoolInfo: investor1_pool_info,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([investor1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    let tx3 = await program.methods
+      .claimProfit()
+      .accounts({
+        investor: investor2.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: swap_pool,
+        withdrawTokenMint: usdc_dev_mint,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+        swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+        investorTokenAccount: investor2_usdc_ata.address,
+        poolShareTokenMint: pool_share_token_a_mint,
+        investorPoolShareTokenAccount: investor2_pool_share_token_a_ata.address,
+        investorPoolInfo: investor2_pool_info,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([investor2])
+      .rpc();
+    console.log("Your transaction signature is ", tx3);
+  });
+
+  it("collects protocol fees", async () => {
+    const protocol_fee_usdc_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      investor1,
+      usdc_dev_mint,
+      wallet.publicKey
+    );
+    const protocol_fee_usdt_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      investor1,
+      usdt_dev_mint,
+      wallet.publicKey
+    );
+
+    let tx = await program.methods
+      .collectProtocolFees()
+      .accounts({
+        collector: user2.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: swap_pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTreasuryTokenAAccount: swap_pool_treasury_token_a_ata,
+        swapPoolTreasuryTokenBAccount: swap_pool_treasury_token_b_ata,
+        protocolFeeTokenAAccount: protocol_fee_usdc_ata.address,
+        protocolFeeTokenBAccount: protocol_fee_usdt_ata.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+  });
+
+  it("keeps recorded profit within the treasury balances", async () => {
+    const pool = await program.account.swapPool.fetch(swap_pool);
+    const treasury_a = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_a_ata
+    );
+    const treasury_b = await getAccount(
+      provider.connection,
+      swap_pool_treasury_token_b_ata
+    );
+
+    const unpaid_a = pool.profitOfTokenAAmountToBeDistributed
+      .sub(pool.profitOfTokenAClaimed)
+      .sub(pool.protocolFeeOfTokenACollected);
+    const unpaid_b = pool.profitOfTokenBAmountToBeDistributed
+      .sub(pool.profitOfTokenBClaimed)
+      .sub(pool.protocolFeeOfTokenBCollected);
+    assert.isTrue(unpaid_a.lte(new BN(treasury_a.amount.toString())));
+    assert.isTrue(unpaid_b.lte(new BN(treasury_b.amount.toString())));
+  });
+
+  // the pool above lives for 360 days, it can only be settled and withdrawn from after that
+  it.skip("withdraws from swap pool", async () => {
+    let settle_tx = await program.methods
+      .settlePool()
+      .accounts({
+        settler: user2.publicKey,
+        signingAuthority: signing_authority,
+        swapPool: swap_pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTokenAAccount: swap_pool_usdc_ata,
+        swapPoolTokenBAccount: swap_pool_usdt_ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    console.log("Your transaction signature is ", settle_tx);
+
+    let tx = await program.methods
+      .withdrawSwapPool(true)
+      .accounts({
+        investor: investor1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: swap_pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTokenAAccount: swap_pool_usdc_ata,
+        swapPoolTokenBAccount: swap_pool_usdt_ata,
+        investorTokenAAccount: investor1_usdc_ata.address,
+        investorTokenBAccount: investor1_usdt_ata.address,
+        poolShareTokenAMint: pool_share_token_a_mint,
+        poolShareTokenBMint: pool_share_token_b_mint,
+        investorPoolShareTokenAAccount: investor1_pool_share_token_a_ata.address,
+        investorPoolShareTokenBAccount: investor1_pool_share_token_b_ata.address,
+        investorPoolInfo: investor1_pool_info,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([investor1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+  });
+
+  it("withdraws from a matured pool only once", async () => {
+    const short_pool_num = new BN(1).toArrayLike(Buffer, "le", 8);
+    let [short_pool, short_pool_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [short_pool_num, Buffer.from("swap-pool")],
+        program.programId
+      );
+    let [short_pool_share_token_a_mint, short_pool_share_token_a_mint_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          short_pool.toBuffer(),
+          usdc_dev_mint.toBuffer(),
+          Buffer.from("pool-share-token"),
+        ],
+        program.programId
+      );
+    let [short_pool_share_token_b_mint, short_pool_share_token_b_mint_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          short_pool.toBuffer(),
+          usdt_dev_mint.toBuffer(),
+          Buffer.from("pool-share-token"),
+        ],
+        program.programId
+      );
+    let [short_pool_usdc_ata, short_pool_usdc_ata_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [short_pool.toBuffer(), usdc_dev_mint.toBuffer()],
+        program.programId
+      );
+    let [short_pool_usdt_ata, short_pool_usdt_ata_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [short_pool.toBuffer(), usdt_dev_mint.toBuffer()],
+        program.programId
+      );
+    let [short_pool_investor1_info, short_pool_investor1_info_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [short_pool.toBuffer(), investor1.publicKey.toBuffer()],
+        program.programId
+      );
+
+    // life of 0 days counted from creation, the pool matures right away
+    let initial_amount = new BN(10_000);
+    let tx = await program.methods
+      .createSwapPool(
+        initial_amount,
+        initial_amount,
+        { stableSwap: {} },
+        new BN(100),
+        new BN(0),
+        4,
+        1000,
+        new BN(1_000),
+        1,
+        new BN(0),
+        { createdAt: {} }
+      )
+      .accounts({
+        owner: wallet.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: short_pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        tokenAMintRegistry: usdc_dev_mint_registry,
+        tokenBMintRegistry: usdt_dev_mint_registry,
+        poolShareTokenAMint: short_pool_share_token_a_mint,
+        poolShareTokenBMint: short_pool_share_token_b_mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const short_pool_share_token_a_ata =
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        investor1,
+        short_pool_share_token_a_mint,
+        investor1.publicKey
+      );
+    const short_pool_share_token_b_ata =
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        investor1,
+        short_pool_share_token_b_mint,
+        investor1.publicKey
+      );
+
+    for (const [token_mint, pool_ata, investor_ata, share_mint, share_ata] of [
+      [
+        usdc_dev_mint,
+        short_pool_usdc_ata,
+        investor1_usdc_ata.address,
+        short_pool_share_token_a_mint,
+        short_pool_share_token_a_ata.address,
+      ],
+      [
+        usdt_dev_mint,
+        short_pool_usdt_ata,
+        investor1_usdt_ata.address,
+        short_pool_share_token_b_mint,
+        short_pool_share_token_b_ata.address,
+      ],
+    ]) {
+      let invest_tx = await program.methods
+        .investSwapPool(initial_amount)
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: short_pool,
+          tokenMint: token_mint,
+          swapPoolTokenAccount: pool_ata,
+          investorTokenAccount: investor_ata,
+          poolShareTokenMint: share_mint,
+          investorPoolShareTokenAccount: share_ata,
+          investorPoolInfo: short_pool_investor1_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+      console.log("Your transaction signature is ", invest_tx);
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    let settle_tx = await program.methods
+      .settlePool()
+      .accounts({
+        settler: user2.publicKey,
+        signingAuthority: signing_authority,
+        swapPool: short_pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        swapPoolTokenAAccount: short_pool_usdc_ata,
+        swapPoolTokenBAccount: short_pool_usdt_ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    console.log("Your transaction signature is ", settle_tx);
+
+    const withdraw = () =>
+      program.methods
+        .withdrawSwapPool(true)
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: short_pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          swapPoolTokenAAccount: short_pool_usdc_ata,
+          swapPoolTokenBAccount: short_pool_usdt_ata,
+          investorTokenAAccount: investor1_usdc_ata.address,
+          investorTokenBAccount: investor1_usdt_ata.address,
+          poolShareTokenAMint: short_pool_share_token_a_mint,
+          poolShareTokenBMint: short_pool_share_token_b_mint,
+          investorPoolShareTokenAAccount: short_pool_share_token_a_ata.address,
+          investorPoolShareTokenBAccount: short_pool_share_token_b_ata.address,
+          investorPoolInfo: short_pool_investor1_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+
+    let withdraw_tx = await withdraw();
+    console.log("Your transaction signature is ", withdraw_tx);
+
+    const share_account = await getAccount(
+      provider.connection,
+      short_pool_share_token_a_ata.address
+    );
+    assert.equal(Number(share_account.amount), 0);
+
+    const investor_pool_info = await program.account.investorPoolInfo.fetch(
+      short_pool_investor1_info
+    );
+    assert.equal(investor_pool_info.poolShareTokenABurned.toNumber(), 10_000);
+
+    let withdrew_again = false;
+    try {
+      await withdraw();
+      withdrew_again = true;
+    } catch (e) {
+      assert.include(e.toString(), "WithdrawAmountIsZero");
+    }
+    assert.isFalse(withdrew_again);
+  });
+
+  it("withdraws both sides of a pool after one-sided swaps", async () => {
+    const pda = async (seeds: Buffer[]) =>
+      (await anchor.web3.PublicKey.findProgramAddress(seeds, program.programId))[0];
+    const state = await program.account.globalState.fetch(global_state);
+    const pool = await pda([
+      state.noOfSwapPools.toArrayLike(Buffer, "le", 8),
+      Buffer.from("swap-pool"),
+    ]);
+    const mints = [usdc_dev_mint, usdt_dev_mint];
+    const investor_token_accounts = [
+      investor1_usdc_ata.address,
+      investor1_usdt_ata.address,
+    ];
+    const share_mints = await Promise.all(
+      mints.map((mint) =>
+        pda([pool.toBuffer(), mint.toBuffer(), Buffer.from("pool-share-token")])
+      )
+    );
+    const share_accounts = await Promise.all(
+      share_mints.map((mint) =>
+        anchor.utils.token.associatedAddress({ mint, owner: investor1.publicKey })
+      )
+    );
+    const pool_token_accounts = await Promise.all(
+      mints.map((mint) => pda([pool.toBuffer(), mint.toBuffer()]))
+    );
+    const treasury_accounts = await Promise.all(
+      mints.map((mint) =>
+        pda([pool.toBuffer(), mint.toBuffer(), Buffer.from("treasury-account")])
+      )
+    );
+    const investor_pool_info = await pda([
+      pool.toBuffer(),
+      investor1.publicKey.toBuffer(),
+    ]);
+
+    // life of 0 days counted from activation, the pool matures as soon as it is filled
+    let tx = await program.methods
+      .createSwapPool(
+        new BN(10_000),
+        new BN(10_000),
+        { stableSwap: {} },
+        new BN(100),
+        new BN(0),
+        4,
+        1000,
+        new BN(1_000),
+        1,
+        new BN(0),
+        { activatedAt: {} }
+      )
+      .accounts({
+        owner: wallet.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        tokenAMintRegistry: usdc_dev_mint_registry,
+        tokenBMintRegistry: usdt_dev_mint_registry,
+        poolShareTokenAMint: share_mints[0],
+        poolShareTokenBMint: share_mints[1],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const invest = (i: number) =>
+      program.methods
+        .investSwapPool(new BN(10_000))
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenMint: mints[i],
+          swapPoolTokenAccount: pool_token_accounts[i],
+          investorTokenAccount: investor_token_accounts[i],
+          poolShareTokenMint: share_mints[i],
+          investorPoolShareTokenAccount: share_accounts[i],
+          investorPoolInfo: investor_pool_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1]);
+    await invest(0).rpc();
+
+    // the swap lands in the same transaction as the activation, before the pool matures
+    const swap = await program.methods
+      .swapToken(new BN(5_000), new BN(1), true, null, 0)
+      .accounts({
+        user: investor1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        userTokenAAccount: investor_token_accounts[0],
+        userTokenBAccount: investor_token_accounts[1],
+        swapPoolTokenAAccount: pool_token_accounts[0],
+        swapPoolTokenBAccount: pool_token_accounts[1],
+        swapPoolTreasuryTokenAAccount: treasury_accounts[0],
+        swapPoolTreasuryTokenBAccount: treasury_accounts[1],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    tx = await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(await invest(1).instruction(), swap),
+      [investor1]
+    );
+    console.log("Your transaction signature is ", tx);
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    // the pool holds more token a than was invested, which used to underflow
+    for (const is_token_a of [true, false]) {
+      tx = await program.methods
+        .withdrawSwapPool(is_token_a)
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          swapPoolTokenAAccount: pool_token_accounts[0],
+          swapPoolTokenBAccount: pool_token_accounts[1],
+          investorTokenAAccount: investor_token_accounts[0],
+          investorTokenBAccount: investor_token_accounts[1],
+          poolShareTokenAMint: share_mints[0],
+          poolShareTokenBMint: share_mints[1],
+          investorPoolShareTokenAAccount: share_accounts[0],
+          investorPoolShareTokenBAccount: share_accounts[1],
+          investorPoolInfo: investor_pool_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+      console.log("Your transaction signature is ", tx);
+    }
+
+    const swap_pool_state = await program.account.swapPool.fetch(pool);
+    assert.deepEqual(swap_pool_state.status, { closed: {} });
+    assert.isAbove(swap_pool_state.tokenAAmountToBeDistributed.toNumber(), 10_000);
+    for (const pool_token_account of pool_token_accounts) {
+      const account = await getAccount(provider.connection, pool_token_account);
+      assert.isAtMost(Number(account.amount), 2);
+    }
+  });
+
+  // creates a pool asking for 10_000 of each token, a stable swap one unless another curve is
+  // given, with helpers for investor1 to invest into it and to swap against it
+  const createTestSwapPool = async (
+    max_days_to_fill: number,
+    swap_life_in_days: number,
+    life_starts_at: { createdAt: {} } | { activatedAt: {} },
+    curve_type:
+      | { constantProduct: {} }
+      | { stableSwap: {} }
+      | { constantSum: {} }
+      | { fixedRatio: {} } = { stableSwap: {} },
+    fixed_ratio: number = 0
+  ) => {
+    const pda = async (seeds: Buffer[]) =>
+      (await anchor.web3.PublicKey.findProgramAddress(seeds, program.programId))[0];
+    const state = await program.account.globalState.fetch(global_state);
+    const pool = await pda([
+      state.noOfSwapPools.toArrayLike(Buffer, "le", 8),
+      Buffer.from("swap-pool"),
+    ]);
+    const mints = [usdc_dev_mint, usdt_dev_mint];
+    const investor_token_accounts = [
+      investor1_usdc_ata.address,
+      investor1_usdt_ata.address,
+    ];
+    const share_mints = await Promise.all(
+      mints.map((mint) =>
+        pda([pool.toBuffer(), mint.toBuffer(), Buffer.from("pool-share-token")])
+      )
+    );
+    const share_accounts = await Promise.all(
+      share_mints.map((mint) =>
+        anchor.utils.token.associatedAddress({ mint, owner: investor1.publicKey })
+      )
+    );
+    const pool_token_accounts = await Promise.all(
+      mints.map((mint) => pda([pool.toBuffer(), mint.toBuffer()]))
+    );
+    const treasury_accounts = await Promise.all(
+      mints.map((mint) =>
+        pda([pool.toBuffer(), mint.toBuffer(), Buffer.from("treasury-account")])
+      )
+    );
+    const investor_pool_info = await pda([
+      pool.toBuffer(),
+      investor1.publicKey.toBuffer(),
+    ]);
+
+    let tx = await program.methods
+      .createSwapPool(
+        new BN(10_000),
+        new BN(10_000),
+        curve_type,
+        new BN(100),
+        new BN(fixed_ratio),
+        4,
+        1000,
+        new BN(1_000),
+        max_days_to_fill,
+        new BN(swap_life_in_days),
+        life_starts_at
+      )
+      .accounts({
+        owner: wallet.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: usdt_dev_mint,
+        tokenAMintRegistry: usdc_dev_mint_registry,
+        tokenBMintRegistry: usdt_dev_mint_registry,
+        poolShareTokenAMint: share_mints[0],
+        poolShareTokenBMint: share_mints[1],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const invest = (i: number, amount: number) =>
+      program.methods
+        .investSwapPool(new BN(amount))
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenMint: mints[i],
+          swapPoolTokenAccount: pool_token_accounts[i],
+          investorTokenAccount: investor_token_accounts[i],
+          poolShareTokenMint: share_mints[i],
+          investorPoolShareTokenAccount: share_accounts[i],
+          investorPoolInfo: investor_pool_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+    const swap = (amount: number = 1_000, is_token_a: boolean = true) =>
+      program.methods
+        .swapToken(new BN(amount), new BN(1), is_token_a, null, 0)
+        .accounts({
+          user: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          userTokenAAccount: investor_token_accounts[0],
+          userTokenBAccount: investor_token_accounts[1],
+          swapPoolTokenAAccount: pool_token_accounts[0],
+          swapPoolTokenBAccount: pool_token_accounts[1],
+          swapPoolTreasuryTokenAAccount: treasury_accounts[0],
+          swapPoolTreasuryTokenBAccount: treasury_accounts[1],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+
+    return { pool, pool_token_accounts, invest, swap };
+  };
+
+  it("cancels a funding pool and rejects illegal status transitions", async () => {
+    // an active pool cannot be cancelled
+    let cancelled = false;
+    try {
+      await program.methods
+        .cancelSwapPool()
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          swapPool: swap_pool,
+        })
+        .rpc();
+      cancelled = true;
+    } catch (e) {
+      assert.include(e.toString(), "InvalidPoolStatusTransition");
+    }
+    assert.isFalse(cancelled);
+
+    const { pool, invest } = await createTestSwapPool(1, 1, { createdAt: {} });
+    await invest(0, 5_000);
+
+    const cancel = () =>
+      program.methods
+        .cancelSwapPool()
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          swapPool: pool,
+        })
+        .rpc();
+    let tx = await cancel();
+    console.log("Your transaction signature is ", tx);
+
+    const swap_pool_state = await program.account.swapPool.fetch(pool);
+    assert.deepEqual(swap_pool_state.status, { cancelled: {} });
+    assert.equal(swap_pool_state.tokenAAmountToBeDistributed.toNumber(), 5_000);
+
+    // a cancelled pool cannot be cancelled again nor take more investments
+    cancelled = false;
+    try {
+      await cancel();
+      cancelled = true;
+    } catch (e) {
+      assert.include(e.toString(), "InvalidPoolStatusTransition");
+    }
+    assert.isFalse(cancelled);
+
+    let invested = false;
+    try {
+      await invest(1, 10_000);
+      invested = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolNotOpenForInvestment");
+    }
+    assert.isFalse(invested);
+  });
+
+  it("settles pools past their fill deadline or maturity", async () => {
+    const settle = (
+      pool: anchor.web3.PublicKey,
+      pool_token_accounts: anchor.web3.PublicKey[]
+    ) =>
+      program.methods
+        .settlePool()
+        .accounts({
+          settler: user2.publicKey,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: usdt_dev_mint,
+          swapPoolTokenAAccount: pool_token_accounts[0],
+          swapPoolTokenBAccount: pool_token_accounts[1],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+
+    // no day to fill, the pool takes no investment once its creation time has passed
+    const unfilled = await createTestSwapPool(0, 1, { createdAt: {} });
+    // life of 0 days counted from creation, the pool matures right after it is filled
+    const short_lived = await createTestSwapPool(1, 0, { createdAt: {} });
+    await short_lived.invest(0, 10_000);
+    await short_lived.invest(1, 10_000);
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    let invested = false;
+    try {
+      await unfilled.invest(0, 5_000);
+      invested = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolNotOpenForInvestment");
+    }
+    assert.isFalse(invested);
+
+    let tx = await settle(unfilled.pool, unfilled.pool_token_accounts);
+    console.log("Your transaction signature is ", tx);
+    let swap_pool_state = await program.account.swapPool.fetch(unfilled.pool);
+    assert.deepEqual(swap_pool_state.status, { expiredUnfilled: {} });
+
+    // the matured pool refuses swaps before anyone settled it
+    let swapped = false;
+    try {
+      await short_lived.swap();
+      swapped = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolMatured");
+    }
+    assert.isFalse(swapped);
+
+    tx = await settle(short_lived.pool, short_lived.pool_token_accounts);
+    console.log("Your transaction signature is ", tx);
+    swap_pool_state = await program.account.swapPool.fetch(short_lived.pool);
+    assert.deepEqual(swap_pool_state.status, { matured: {} });
+    assert.equal(swap_pool_state.tokenAAmountToBeDistributed.toNumber(), 10_000);
+    assert.equal(swap_pool_state.tokenBAmountToBeDistributed.toNumber(), 10_000);
+
+    // a settled pool is not due again
+    let settled_again = false;
+    try {
+      await settle(short_lived.pool, short_lived.pool_token_accounts);
+      settled_again = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolNotDueForSettlement");
+    }
+    assert.isFalse(settled_again);
+  });
+
+  it("counts the life of a pool from its activation", async () => {
+    const created = await createTestSwapPool(1, 1, { createdAt: {} });
+    let swap_pool_state = await program.account.swapPool.fetch(created.pool);
+    assert.equal(
+      swap_pool_state.maturesAt.toNumber(),
+      swap_pool_state.createdAt.toNumber() + 86_400
+    );
+
+    // the maturity of the pool is only known once it is filled
+    const { pool, invest } = await createTestSwapPool(1, 1, { activatedAt: {} });
+    swap_pool_state = await program.account.swapPool.fetch(pool);
+    assert.equal(swap_pool_state.activatedAt.toNumber(), 0);
+    assert.equal(swap_pool_state.maturesAt.toNumber(), 0);
+
+    await invest(0, 10_000);
+    await invest(1, 10_000);
+
+    swap_pool_state = await program.account.swapPool.fetch(pool);
+    assert.deepEqual(swap_pool_state.status, { active: {} });
+    assert.isAtLeast(
+      swap_pool_state.activatedAt.toNumber(),
+      swap_pool_state.createdAt.toNumber()
+    );
+    assert.equal(
+      swap_pool_state.maturesAt.toNumber(),
+      swap_pool_state.activatedAt.toNumber() + 86_400
+    );
+
+    // a life whose end does not fit a timestamp is refused at creation
+    let created_forever = false;
+    try {
+      await createTestSwapPool(1, Number.MAX_SAFE_INTEGER, { activatedAt: {} });
+      created_forever = true;
+    } catch (e) {
+      assert.include(e.toString(), "MathOverflow");
+    }
+    assert.isFalse(created_forever);
+  });
+
+  it("swaps at a constant sum and at a fixed ratio within the reserves", async () => {
+    const balances = async () =>
+      Promise.all(
+        [investor1_usdc_ata.address, investor1_usdt_ata.address].map(
+          async (address) =>
+            Number((await getAccount(provider.connection, address)).amount)
+        )
+      );
+    const expectInsufficientReserves = async (swap: Promise<string>) => {
+      let swapped = false;
+      try {
+        await swap;
+        swapped = true;
+      } catch (e) {
+        assert.include(e.toString(), "InsufficientPoolReserves");
+      }
+      assert.isFalse(swapped);
+    };
+
+    // tokens are swapped 1:1 minus the fee of 4 bps levied on the output
+    const constant_sum = await createTestSwapPool(1, 1, { createdAt: {} }, {
+      constantSum: {},
+    });
+    await constant_sum.invest(0, 10_000);
+    await constant_sum.invest(1, 10_000);
+
+    let [usdc_before, usdt_before] = await balances();
+    let tx = await constant_sum.swap(5_000, true);
+    console.log("Your transaction signature is ", tx);
+    let [usdc_after, usdt_after] = await balances();
+    assert.equal(usdc_before - usdc_after, 5_000);
+    assert.equal(usdt_after - usdt_before, 4_998);
+
+    // 5_000 of token b are left in the pool
+    await expectInsufficientReserves(constant_sum.swap(5_001, true));
+
+    // 2 units of token b for each unit of token a
+    const fixed_ratio = await createTestSwapPool(
+      1,
+      1,
+      { createdAt: {} },
+      { fixedRatio: {} },
+      2_000_000
+    );
+    await fixed_ratio.invest(0, 10_000);
+    await fixed_ratio.invest(1, 10_000);
+
+    [usdc_before, usdt_before] = await balances();
+    tx = await fixed_ratio.swap(2_500, true);
+    console.log("Your transaction signature is ", tx);
+    [usdc_after, usdt_after] = await balances();
+    assert.equal(usdc_before - usdc_after, 2_500);
+    assert.equal(usdt_after - usdt_before, 4_998);
+
+    [usdc_before, usdt_before] = await balances();
+    tx = await fixed_ratio.swap(4_000, false);
+    console.log("Your transaction signature is ", tx);
+    [usdc_after, usdt_after] = await balances();
+    assert.equal(usdt_before - usdt_after, 4_000);
+    assert.equal(usdc_after - usdc_before, 2_000);
+
+    // 9_000 of token b are left, worth 4_500 of token a
+    await expectInsufficientReserves(fixed_ratio.swap(4_501, true));
+  });
+
+  it("swaps between any two tokens of a multi asset pool", async () => {
+    const [uxd_dev_mint_registry] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [uxd_dev_mint.toBuffer(), Buffer.from("token-mint-registry")],
+        program.programId
+      );
+
+    const investor1_uxd_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      investor1,
+      uxd_dev_mint,
+      investor1.publicKey
+    );
+    await program.methods
+      .getTestTokens(new anchor.BN(1_000_000))
+      .accounts({
+        investor: investor1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        tokenMint: uxd_dev_mint,
+        tokenMintRegistry: uxd_dev_mint_registry,
+        investorTokenAccount: investor1_uxd_ata.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([investor1])
+      .rpc();
+
+    const [multi_asset_pool] = await anchor.web3.PublicKey.findProgramAddress(
+      [new BN(0).toArrayLike(Buffer, "le", 8), Buffer.from("multi-asset-pool")],
+      program.programId
+    );
+    const token_mints = [usdc_dev_mint, usdt_dev_mint, uxd_dev_mint];
+    const investor_token_accounts = [
+      investor1_usdc_ata.address,
+      investor1_usdt_ata.address,
+      investor1_uxd_ata.address,
+    ];
+    const pda = async (seeds: Buffer[]) =>
+      (await anchor.web3.PublicKey.findProgramAddress(seeds, program.programId))[0];
+
+    await program.methods
+      .createMultiAssetPool(
+        new BN(10_000),
+        new BN(100),
+        100,
+        1000,
+        new BN(1_000),
+        30,
+        new BN(360),
+        { activatedAt: {} }
+      )
+      .accounts({
+        owner: wallet.publicKey,
+        globalState: global_state,
+        multiAssetPool: multi_asset_pool,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        [
+          usdc_dev_mint_registry,
+          usdc_dev_mint,
+          usdt_dev_mint_registry,
+          usdt_dev_mint,
+          uxd_dev_mint_registry,
+          uxd_dev_mint,
+        ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+      )
+      .rpc();
+
+    const multi_asset_investor_info = await pda([
+      multi_asset_pool.toBuffer(),
+      investor1.publicKey.toBuffer(),
+    ]);
+    for (let i = 0; i < token_mints.length; i++) {
+      const pool_share_token_mint = await pda([
+        multi_asset_pool.toBuffer(),
+        token_mints[i].toBuffer(),
+        Buffer.from("pool-share-token"),
+      ]);
+      const multi_asset_pool_token_account = await pda([
+        multi_asset_pool.toBuffer(),
+        token_mints[i].toBuffer(),
+      ]);
+      await program.methods
+        .initializeMultiAssetPoolToken(i)
+        .accounts({
+          owner: wallet.publicKey,
+          signingAuthority: signing_authority,
+          multiAssetPool: multi_asset_pool,
+          tokenMint: token_mints[i],
+          poolShareTokenMint: pool_share_token_mint,
+          multiAssetPoolTokenAccount: multi_asset_pool_token_account,
+          multiAssetPoolTreasuryTokenAccount: await pda([
+            multi_asset_pool.toBuffer(),
+            token_mints[i].toBuffer(),
+            Buffer.from("treasury-account"),
+          ]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      const investor_pool_share_ata = await anchor.utils.token.associatedAddress({
+        mint: pool_share_token_mint,
+        owner: investor1.publicKey,
+      });
+      await program.methods
+        .investMultiAssetPool(i, new BN(10_000))
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          multiAssetPool: multi_asset_pool,
+          tokenMint: token_mints[i],
+          multiAssetPoolTokenAccount: multi_asset_pool_token_account,
+          investorTokenAccount: investor_token_accounts[i],
+          poolShareTokenMint: pool_share_token_mint,
+          investorPoolShareTokenAccount: investor_pool_share_ata,
+          multiAssetInvestorInfo: multi_asset_investor_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+    }
+
+    let pool = await program.account.multiAssetPool.fetch(multi_asset_pool);
+    assert.deepEqual(pool.status, { active: {} });
+
+    const balance_before = await getAccount(
+      provider.connection,
+      investor1_uxd_ata.address
+    );
+    const swap = async () =>
+      program.methods
+        .swapMultiAssetPool(0, 2, new BN(1_000), new BN(1), null)
+        .accounts({
+          user: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          multiAssetPool: multi_asset_pool,
+          userTokenInAccount: investor1_usdc_ata.address,
+          userTokenOutAccount: investor1_uxd_ata.address,
+          multiAssetPoolTokenInAccount: await pda([
+            multi_asset_pool.toBuffer(),
+            usdc_dev_mint.toBuffer(),
+          ]),
+          multiAssetPoolTokenOutAccount: await pda([
+            multi_asset_pool.toBuffer(),
+            uxd_dev_mint.toBuffer(),
+          ]),
+          multiAssetPoolTreasuryTokenOutAccount: await pda([
+            multi_asset_pool.toBuffer(),
+            uxd_dev_mint.toBuffer(),
+            Buffer.from("treasury-account"),
+          ]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([investor1])
+        .rpc();
+    const tx = await swap();
+    console.log("Your transaction signature is ", tx);
+
+    const balance_after = await getAccount(
+      provider.connection,
+      investor1_uxd_ata.address
+    );
+    const received = Number(balance_after.amount) - Number(balance_before.amount);
+    // close to par on a balanced stable pool, less the 1% fee
+    assert.isTrue(received > 950 && received < 1_000);
+
+    pool = await program.account.multiAssetPool.fetch(multi_asset_pool);
+    assert.equal(pool.reserves[0].toNumber(), 11_000);
+    assert.equal(pool.reserves[1].toNumber(), 10_000);
+    assert.isTrue(pool.profitsToBeDistributed[2].toNumber() > 0);
+
+    const set_pause = (paused: boolean) =>
+      program.methods
+        .setMultiAssetPoolPause(paused)
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          multiAssetPool: multi_asset_pool,
+        })
+        .rpc();
+
+    await set_pause(true);
+    let swapped_while_paused = false;
+    try {
+      await swap();
+      swapped_while_paused = true;
+    } catch (e) {
+      assert.include(e.toString(), "SwapPoolPaused");
+    }
+    assert.isFalse(swapped_while_paused);
+
+    await set_pause(false);
+    await swap();
+  });
+
+  it("prices mints with different decimals at par", async () => {
+    const payer = (wallet as anchor.Wallet).payer;
+    const nine_decimals_mint = await createMint(
+      provider.connection,
+      payer,
+      wallet.publicKey,
+      null,
+      9
+    );
+    const [nine_decimals_mint_registry] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [nine_decimals_mint.toBuffer(), Buffer.from("token-mint-registry")],
+        program.programId
+      );
+    await program.methods
+      .registerTokenMint()
+      .accounts({
+        admin: wallet.publicKey,
+        globalState: global_state,
+        tokenMint: nine_decimals_mint,
+        tokenMintRegistry: nine_decimals_mint_registry,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const investor1_nine_decimals_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      investor1,
+      nine_decimals_mint,
+      investor1.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      nine_decimals_mint,
+      investor1_nine_decimals_ata.address,
+      wallet.publicKey,
+      100_000_000
+    );
+
+    const pda = async (seeds: Buffer[]) =>
+      (await anchor.web3.PublicKey.findProgramAddress(seeds, program.programId))[0];
+    const state = await program.account.globalState.fetch(global_state);
+    const pool = await pda([
+      state.noOfSwapPools.toArrayLike(Buffer, "le", 8),
+      Buffer.from("swap-pool"),
+    ]);
+    const tokens = [
+      [usdc_dev_mint, usdc_dev_mint_registry, investor1_usdc_ata.address, 10_000],
+      [
+        nine_decimals_mint,
+        nine_decimals_mint_registry,
+        investor1_nine_decimals_ata.address,
+        10_000_000,
+      ],
+    ] as const;
+    const share_mints = await Promise.all(
+      tokens.map(([mint]) =>
+        pda([pool.toBuffer(), mint.toBuffer(), Buffer.from("pool-share-token")])
+      )
+    );
+    const pool_token_accounts = await Promise.all(
+      tokens.map(([mint]) => pda([pool.toBuffer(), mint.toBuffer()]))
+    );
+
+    const create = (initial_amount_b: number) =>
+      program.methods
+        .createSwapPool(
+          new BN(10_000),
+          new BN(initial_amount_b),
+          { stableSwap: {} },
+          new BN(100),
+          new BN(0),
+          4,
+          1000,
+          new BN(1_000),
+          30,
+          new BN(360),
+          { activatedAt: {} }
+        )
+        .accounts({
+          owner: wallet.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenAMint: usdc_dev_mint,
+          tokenBMint: nine_decimals_mint,
+          tokenAMintRegistry: usdc_dev_mint_registry,
+          tokenBMintRegistry: nine_decimals_mint_registry,
+          poolShareTokenAMint: share_mints[0],
+          poolShareTokenBMint: share_mints[1],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    // equal amounts of units are not worth the same once the decimals differ
+    let created_unit_for_unit = false;
+    try {
+      await create(10_000);
+      created_unit_for_unit = true;
+    } catch (e) {
+      assert.include(e.toString(), "InitialAmountsNotSame");
+    }
+    assert.isFalse(created_unit_for_unit);
+
+    await create(10_000_000);
+    const share_mint_b = await provider.connection.getParsedAccountInfo(
+      share_mints[1]
+    );
+    assert.equal((share_mint_b.value.data as any).parsed.info.decimals, 9);
+
+    const investor_pool_info = await pda([
+      pool.toBuffer(),
+      investor1.publicKey.toBuffer(),
+    ]);
+    for (let i = 0; i < tokens.length; i++) {
+      const [mint, , investor_token_account, amount] = tokens[i];
+      await program.methods
+        .investSwapPool(new BN(amount))
+        .accounts({
+          investor: investor1.publicKey,
+          globalState: global_state,
+          signingAuthority: signing_authority,
+          swapPool: pool,
+          tokenMint: mint,
+          swapPoolTokenAccount: pool_token_accounts[i],
+          investorTokenAccount: investor_token_account,
+          poolShareTokenMint: share_mints[i],
+          investorPoolShareTokenAccount: await anchor.utils.token.associatedAddress({
+            mint: share_mints[i],
+            owner: investor1.publicKey,
+          }),
+          investorPoolInfo: investor_pool_info,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([investor1])
+        .rpc();
+    }
+
+    const balance_before = await getAccount(
+      provider.connection,
+      investor1_nine_decimals_ata.address
+    );
+    const tx = await program.methods
+      .swapToken(new BN(1_000), new BN(1), true, null, 0)
+      .accounts({
+        user: investor1.publicKey,
+        globalState: global_state,
+        signingAuthority: signing_authority,
+        swapPool: pool,
+        tokenAMint: usdc_dev_mint,
+        tokenBMint: nine_decimals_mint,
+        userTokenAAccount: investor1_usdc_ata.address,
+        userTokenBAccount: investor1_nine_decimals_ata.address,
+        swapPoolTokenAAccount: pool_token_accounts[0],
+        swapPoolTokenBAccount: pool_token_accounts[1],
+        swapPoolTreasuryTokenAAccount: await pda([
+          pool.toBuffer(),
+          usdc_dev_mint.toBuffer(),
+          Buffer.from("treasury-account"),
+        ]),
+        swapPoolTreasuryTokenBAccount: await pda([
+          pool.toBuffer(),
+          nine_decimals_mint.toBuffer(),
+          Buffer.from("treasury-account"),
+        ]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([investor1])
+      .rpc();
+    console.log("Your transaction signature is ", tx);
+
+    const balance_after = await getAccount(
+      provider.connection,
+      investor1_nine_decimals_ata.address
+    );
+    // 1_000 units of usdc are worth 1_000_000 units of a 9 decimals coin
+    const received = Number(balance_after.amount) - Number(balance_before.amount);
+    assert.isTrue(received > 950_000 && received < 1_000_000);
+  });
+
+  it("migrates accounts in the legacy layout", async () => {
+    // fixture accounts loaded by the validator, written in the baseline layout with whole
+    // percent fees and the active_for_swap, open_for_investment, open_for_withdrawal flags
+    const legacy_swap_pool = new anchor.web3.PublicKey(
+      "4JMQkiPx9nTTW5XMFWrhXGUojDSz4rPtK3oyZb53JS9o"
+    );
+    const legacy_investor_pool_info = new anchor.web3.PublicKey(
+      "vBQgAfaLYKsqfb6X394Ayuq4947B8d2tQPrMMTKUzHm"
+    );
+    const legacy_token_a_mint = new anchor.web3.PublicKey(
+      "APUdxnKCthC25ycU7FUZYsmfK2goCWkcMsubWQ2nSNWF"
+    );
+    const legacy_token_b_mint = new anchor.web3.PublicKey(
+      "2UZYkLB5mf9ysxyiPUg3yzW22i2jrZyJUeU2XaiqR7NH"
+    );
+    const [legacy_treasury_token_a] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          legacy_swap_pool.toBuffer(),
+          legacy_token_a_mint.toBuffer(),
+          Buffer.from("treasury-account"),
+        ],
+        program.programId
+      );
+    const [legacy_treasury_token_b] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          legacy_swap_pool.toBuffer(),
+          legacy_token_b_mint.toBuffer(),
+          Buffer.from("treasury-account"),
+        ],
+        program.programId
+      );
+
+    const migrate_swap_pool = () =>
+      program.methods
+        .migrateSwapPool()
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          swapPool: legacy_swap_pool,
+          tokenAMint: legacy_token_a_mint,
+          tokenBMint: legacy_token_b_mint,
+          signingAuthority: signing_authority,
+          // the fixture treasury of token a holds 180, the one of token b was never created
+          swapPoolTreasuryTokenAAccount: legacy_treasury_token_a,
+          swapPoolTreasuryTokenBAccount: legacy_treasury_token_b,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    let tx = await migrate_swap_pool();
+    console.log("Your transaction signature is ", tx);
+
+    const pool = await program.account.swapPool.fetch(legacy_swap_pool);
+    assert.equal(pool.poolNumber.toNumber(), 900);
+    assert.deepEqual(pool.status, { active: {} });
+    assert.deepEqual(pool.curveType, { constantProduct: {} });
+    assert.deepEqual(pool.lifeStartsAt, { createdAt: {} });
+    assert.equal(pool.swapFeeBps, 100);
+    assert.equal(pool.swapverseFeeBps, 1_000);
+    assert.equal(pool.activatedAt.toNumber(), 1_700_050_000);
+    assert.equal(
+      pool.maturesAt.toNumber(),
+      1_700_000_000 + 3650 * 24 * 60 * 60
+    );
+    assert.equal(pool.tokenADecimals, 6);
+    assert.equal(pool.tokenBDecimals, 9);
+    assert.equal(pool.tokenAAmountToBeDistributed.toNumber(), 1_000_000);
+    // legacy profits of 500 and 300 were credited to the wrong tokens, they are swapped back
+    // and what left the treasuries is counted as claimed
+    assert.equal(pool.profitOfTokenAAmountToBeDistributed.toNumber(), 300);
+    assert.equal(pool.profitOfTokenBAmountToBeDistributed.toNumber(), 500);
+    assert.equal(pool.profitOfTokenAClaimed.toNumber(), 120);
+    assert.equal(pool.profitOfTokenBClaimed.toNumber(), 500);
+
+    let tx2 = await program.methods
+      .migrateInvestorPoolInfo()
+      .accounts({
+        payer: user1.publicKey,
+        investorPoolInfo: legacy_investor_pool_info,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user1])
+      .rpc();
+    console.log("Your transaction signature is ", tx2);
+
+    const investor_pool_info = await program.account.investorPoolInfo.fetch(
+      legacy_investor_pool_info
+    );
+    assert.isTrue(investor_pool_info.swapPool.equals(legacy_swap_pool));
+    assert.equal(investor_pool_info.profitForTokenAWithdrawn.toNumber(), 120);
+    assert.equal(investor_pool_info.poolShareTokenABurned.toNumber(), 0);
+    assert.equal(investor_pool_info.poolShareTokenBBurned.toNumber(), 0);
+
+    let migrated_again = false;
+    try {
+      await migrate_swap_pool();
+      migrated_again = true;
+    } catch (e) {
+      assert.include(e.toString(), "AccountNotInLegacyLayout");
+    }
+    assert.isFalse(migrated_again);
+
+    // the same pool once open for withdrawal, its paid out shares were never burned
+    const legacy_withdrawable_swap_pool = new anchor.web3.PublicKey(
+      "3CtRptU9cu5Ddc468HSaPUPrbDiSgWH57XLm6oPWgupF"
+    );
+    const withdrawable_treasury_accounts = await Promise.all(
+      [legacy_token_a_mint, legacy_token_b_mint].map(
+        async (mint) =>
+          (
+            await anchor.web3.PublicKey.findProgramAddress(
+              [
+                legacy_withdrawable_swap_pool.toBuffer(),
+                mint.toBuffer(),
+                Buffer.from("treasury-account"),
+              ],
+              program.programId
+            )
+          )[0]
+      )
+    );
+    let migrated_withdrawable = false;
+    try {
+      await program.methods
+        .migrateSwapPool()
+        .accounts({
+          admin: wallet.publicKey,
+          globalState: global_state,
+          swapPool: legacy_withdrawable_swap_pool,
+          tokenAMint: legacy_token_a_mint,
+          tokenBMint: legacy_token_b_mint,
+          signingAuthority: signing_authority,
+          swapPoolTreasuryTokenAAccount: withdrawable_treasury_accounts[0],
+          swapPoolTreasuryTokenBAccount: withdrawable_treasury_accounts[1],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      migrated_withdrawable = true;
+    } catch (e) {
+      assert.include(e.toString(), "LegacySwapPoolOpenForWithdrawal");
+    }
+    assert.isFalse(migrated_withdrawable);
   });
 });